@@ -0,0 +1,119 @@
+use crate::commands::config;
+use crate::commands::hardware::AppState;
+use crate::services::alert_history_service::{AlertHistoryFilter, AlertRecord};
+use crate::services::webhook_service::{self, WebhookDelivery};
+use serde_json::json;
+use tauri::command;
+
+///
+/// ## アラートの履歴を取得（新しい順）
+///
+/// - param filter: `Option<AlertHistoryFilter>` ルール・指標・期間・未確認のみなどの条件
+///
+#[command]
+pub fn get_alert_history(
+  state: tauri::State<'_, AppState>,
+  filter: Option<AlertHistoryFilter>,
+) -> Vec<AlertRecord> {
+  let history = state.alert_history.lock().unwrap();
+  history.list(&filter.unwrap_or_default())
+}
+
+///
+/// ## アラートを確認済みにする
+///
+/// - param ids: `Vec<String>` 履歴の ID（`AlertRecord.id`）
+/// - return: `usize` 確認済みにした件数
+///
+#[command]
+pub fn acknowledge_alerts(
+  state: tauri::State<'_, AppState>,
+  ids: Vec<String>,
+) -> Result<usize, String> {
+  let (count, pending) = {
+    let mut history = state.alert_history.lock().unwrap();
+    let count = history.acknowledge(&ids, chrono::Utc::now().timestamp_millis());
+    (count, history.pending_save()?)
+  };
+
+  // ファイルへの書き込み中にサンプリングを止めないよう、ロックを解放してから書き込む
+  if let Some(pending) = pending {
+    pending.write()?;
+  }
+  Ok(count)
+}
+
+///
+/// ## アラートの履歴を削除
+///
+/// - param before: `Option<i64>` 指定した時刻（UNIX エポックからのミリ秒）より前の履歴のみ削除
+/// - return: `usize` 削除した件数
+///
+#[command]
+pub fn clear_alert_history(
+  state: tauri::State<'_, AppState>,
+  before: Option<i64>,
+) -> Result<usize, String> {
+  let (removed, pending) = {
+    let mut history = state.alert_history.lock().unwrap();
+    let removed = history.clear(before);
+    (removed, history.pending_save()?)
+  };
+
+  if let Some(pending) = pending {
+    pending.write()?;
+  }
+  Ok(removed)
+}
+
+///
+/// ## Webhook の送信結果を取得（新しい順）
+///
+/// - param limit: `usize` 取得する件数
+///
+#[command]
+pub fn get_webhook_deliveries(
+  state: tauri::State<'_, AppState>,
+  limit: usize,
+) -> Vec<WebhookDelivery> {
+  state.webhook_delivery_log.lock().unwrap().recent(limit)
+}
+
+///
+/// ## Webhook にテスト送信
+///
+/// - テンプレートがある場合はサンプルの値で展開して送信する
+/// - 結果は送信結果の記録にも追加する
+///
+#[command]
+pub async fn test_webhook(
+  state: tauri::State<'_, AppState>,
+  config_state: tauri::State<'_, config::AppState>,
+  id: String,
+) -> Result<WebhookDelivery, String> {
+  let target = config_state
+    .get_webhooks()
+    .into_iter()
+    .find(|webhook| webhook.id == id)
+    .ok_or_else(|| format!("Webhook not found: {}", id))?;
+
+  let body = match &target.body_template {
+    Some(template) => webhook_service::render_template(
+      template,
+      &webhook_service::template_values_sample(),
+    )?,
+    None => json!({ "test": true, "webhookName": target.name }),
+  };
+
+  let client = reqwest::Client::new();
+  let (attempts, result) = webhook_service::deliver(&client, &target, &body).await;
+  let delivery = webhook_service::to_delivery(&target, None, None, attempts, result);
+
+  state
+    .webhook_delivery_log
+    .lock()
+    .unwrap()
+    .push(delivery.clone());
+
+  Ok(delivery)
+}
@@ -0,0 +1,30 @@
+use crate::services::cgroup_service;
+use tauri::command;
+
+///
+/// ## 自プロセスが所属する cgroup の使用状況を取得
+///
+/// - param root: `Option<String>` cgroup v2 のマウントポイント（省略時は `/sys/fs/cgroup`、`/sys/fs/cgroup` 配下のみ指定できる）
+/// - return: `cgroup_service::CgroupStats` cpu.stat / memory / io.stat / pids の値
+///
+#[command]
+pub fn get_cgroup_usage(
+  root: Option<String>,
+) -> Result<cgroup_service::CgroupStats, String> {
+  let root = cgroup_service::resolve_root(root)?;
+  cgroup_service::get_current_cgroup_stats(&root)
+}
+
+///
+/// ## cgroup 階層全体の使用状況を取得
+///
+/// - param root: `Option<String>` cgroup v2 のマウントポイント（省略時は `/sys/fs/cgroup`、`/sys/fs/cgroup` 配下のみ指定できる）
+/// - return: `cgroup_service::CgroupNode` cgroup ごとの使用状況をツリー形式で返す
+///
+#[command]
+pub fn get_cgroup_tree(
+  root: Option<String>,
+) -> Result<cgroup_service::CgroupNode, String> {
+  let root = cgroup_service::resolve_root(root)?;
+  cgroup_service::get_cgroup_tree(&root)
+}
@@ -1,4 +1,16 @@
 use crate::enums::hardware;
+use crate::enums::metric::MetricKind;
+use crate::enums::process::ProcessCpuMode;
+use crate::services::alert_service::AlertRule;
+use crate::services::anomaly_service::AnomalyDetectorSettings;
+use crate::services::influx_service::{self, InfluxSettings};
+use crate::services::memory_leak_service::MemoryLeakSettings;
+use crate::services::metric_history_service::MetricSample;
+use crate::services::otlp_service::OtlpSettings;
+use crate::services::process_watch_service::ProcessWatchRule;
+use crate::services::prometheus_service::{PrometheusExporter, PrometheusSettings};
+use crate::services::silence_service::{MaintenanceWindow, Silence};
+use crate::services::webhook_service::WebhookTarget;
 use crate::utils::file::get_app_data_dir;
 use crate::{log_debug, log_error, log_info, log_internal, log_warn, utils};
 use serde::{Deserialize, Serialize};
@@ -28,6 +40,30 @@ pub struct Settings {
   display_targets: Vec<hardware::HardwareType>,
   graph_size: String,
   state: StateSettings,
+  #[serde(default)]
+  process_watches: Vec<ProcessWatchRule>,
+  #[serde(default)]
+  memory_leak: MemoryLeakSettings,
+  #[serde(default)]
+  process_cpu_mode: ProcessCpuMode,
+  #[serde(default)]
+  alert_rules: Vec<AlertRule>,
+  #[serde(default)]
+  alert_commands_enabled: bool, // 安全のため既定では無効
+  #[serde(default)]
+  webhooks: Vec<WebhookTarget>,
+  #[serde(default)]
+  anomaly_detectors: Vec<AnomalyDetectorSettings>, // 指標ごとに 1件
+  #[serde(default)]
+  maintenance_windows: Vec<MaintenanceWindow>,
+  #[serde(default)]
+  silences: Vec<Silence>, // 再起動しても継続するよう保存する
+  #[serde(default)]
+  prometheus: PrometheusSettings,
+  #[serde(default)]
+  influx: InfluxSettings,
+  #[serde(default)]
+  otlp: OtlpSettings,
 }
 
 impl Default for Settings {
@@ -45,6 +81,18 @@ impl Default for Settings {
       state: StateSettings {
         display: "dashboard".to_string(),
       },
+      process_watches: vec![],
+      memory_leak: MemoryLeakSettings::default(),
+      process_cpu_mode: ProcessCpuMode::default(),
+      alert_rules: vec![],
+      alert_commands_enabled: false,
+      webhooks: vec![],
+      anomaly_detectors: vec![],
+      maintenance_windows: vec![],
+      silences: vec![],
+      prometheus: PrometheusSettings::default(),
+      influx: InfluxSettings::default(),
+      otlp: OtlpSettings::default(),
     }
   }
 }
@@ -160,6 +208,167 @@ impl Settings {
     }
     self.write_file()
   }
+
+  pub fn add_process_watch(&mut self, rule: ProcessWatchRule) -> Result<(), String> {
+    self.process_watches.push(rule);
+    self.write_file()
+  }
+
+  pub fn remove_process_watch(&mut self, id: &str) -> Result<(), String> {
+    self.process_watches.retain(|watch| watch.id != id);
+    self.write_file()
+  }
+
+  pub fn set_memory_leak(
+    &mut self,
+    new_settings: MemoryLeakSettings,
+  ) -> Result<(), String> {
+    self.memory_leak = new_settings;
+    self.write_file()
+  }
+
+  pub fn set_process_cpu_mode(&mut self, new_mode: ProcessCpuMode) -> Result<(), String> {
+    self.process_cpu_mode = new_mode;
+    self.write_file()
+  }
+
+  pub fn add_alert_rule(&mut self, rule: AlertRule) -> Result<(), String> {
+    self.alert_rules.push(rule);
+    self.write_file()
+  }
+
+  pub fn update_alert_rule(&mut self, rule: AlertRule) -> Result<(), String> {
+    if let Some(alert_rule) = self
+      .alert_rules
+      .iter_mut()
+      .find(|alert_rule| alert_rule.id == rule.id)
+    {
+      *alert_rule = rule;
+    }
+    self.write_file()
+  }
+
+  pub fn add_webhook(&mut self, webhook: WebhookTarget) -> Result<(), String> {
+    self.webhooks.push(webhook);
+    self.write_file()
+  }
+
+  pub fn update_webhook(&mut self, webhook: WebhookTarget) -> Result<(), String> {
+    if let Some(target) = self
+      .webhooks
+      .iter_mut()
+      .find(|target| target.id == webhook.id)
+    {
+      *target = webhook;
+    }
+    self.write_file()
+  }
+
+  pub fn remove_webhook(&mut self, id: &str) -> Result<(), String> {
+    self.webhooks.retain(|target| target.id != id);
+
+    // 削除した Webhook を参照しているルールからも外す
+    for rule in self.alert_rules.iter_mut() {
+      rule.webhook_ids.retain(|webhook_id| webhook_id != id);
+    }
+    self.write_file()
+  }
+
+  pub fn set_alert_commands_enabled(&mut self, enabled: bool) -> Result<(), String> {
+    self.alert_commands_enabled = enabled;
+    self.write_file()
+  }
+
+  pub fn remove_alert_rule(&mut self, id: &str) -> Result<(), String> {
+    self.alert_rules.retain(|alert_rule| alert_rule.id != id);
+    self.write_file()
+  }
+
+  ///
+  /// ## 異常検知の設定を追加・更新（同じ指標の設定は置き換える）
+  ///
+  pub fn set_anomaly_detector(
+    &mut self,
+    detector: AnomalyDetectorSettings,
+  ) -> Result<(), String> {
+    match self
+      .anomaly_detectors
+      .iter_mut()
+      .find(|current| current.metric == detector.metric)
+    {
+      Some(current) => *current = detector,
+      None => self.anomaly_detectors.push(detector),
+    }
+    self.write_file()
+  }
+
+  pub fn add_maintenance_window(
+    &mut self,
+    window: MaintenanceWindow,
+  ) -> Result<(), String> {
+    self.maintenance_windows.push(window);
+    self.write_file()
+  }
+
+  pub fn update_maintenance_window(
+    &mut self,
+    window: MaintenanceWindow,
+  ) -> Result<(), String> {
+    if let Some(current) = self
+      .maintenance_windows
+      .iter_mut()
+      .find(|current| current.id == window.id)
+    {
+      *current = window;
+    }
+    self.write_file()
+  }
+
+  pub fn remove_maintenance_window(&mut self, id: &str) -> Result<(), String> {
+    self.maintenance_windows.retain(|window| window.id != id);
+    self.write_file()
+  }
+
+  ///
+  /// ## 一時的な通知の停止を追加（期限切れのものは削除する）
+  ///
+  pub fn add_silence(&mut self, silence: Silence) -> Result<(), String> {
+    self
+      .silences
+      .retain(|current| current.is_active(silence.created_at));
+    self.silences.push(silence);
+    self.write_file()
+  }
+
+  pub fn remove_silence(&mut self, id: &str) -> Result<(), String> {
+    self.silences.retain(|silence| silence.id != id);
+    self.write_file()
+  }
+
+  pub fn set_prometheus(
+    &mut self,
+    new_settings: PrometheusSettings,
+  ) -> Result<(), String> {
+    self.prometheus = new_settings;
+    self.write_file()
+  }
+
+  pub fn set_influx(&mut self, new_settings: InfluxSettings) -> Result<(), String> {
+    self.influx = new_settings;
+    self.write_file()
+  }
+
+  pub fn set_otlp(&mut self, new_settings: OtlpSettings) -> Result<(), String> {
+    self.otlp = new_settings;
+    self.write_file()
+  }
+
+  pub fn remove_anomaly_detector(&mut self, metric: MetricKind) -> Result<(), String> {
+    self
+      .anomaly_detectors
+      .retain(|detector| detector.metric != metric);
+    self.write_file()
+  }
 }
 
 #[derive(Debug)]
@@ -173,6 +382,100 @@ impl AppState {
       settings: Mutex::from(Settings::new()),
     }
   }
+
+  ///
+  /// ## プロセスの監視ルールを取得（サンプリングのループから参照）
+  ///
+  pub fn get_process_watches(&self) -> Vec<ProcessWatchRule> {
+    self.settings.lock().unwrap().process_watches.clone()
+  }
+
+  ///
+  /// ## メモリリーク検出の設定を取得（サンプリングのループから参照）
+  ///
+  pub fn get_memory_leak_settings(&self) -> MemoryLeakSettings {
+    self.settings.lock().unwrap().memory_leak.clone()
+  }
+
+  ///
+  /// ## プロセスの CPU 使用率の表し方を取得
+  ///
+  pub fn get_process_cpu_mode(&self) -> ProcessCpuMode {
+    self.settings.lock().unwrap().process_cpu_mode
+  }
+
+  ///
+  /// ## アラートのルールを取得（サンプリングのループから参照）
+  ///
+  pub fn get_alert_rules(&self) -> Vec<AlertRule> {
+    self.settings.lock().unwrap().alert_rules.clone()
+  }
+
+  ///
+  /// ## アラートの発生時にコマンドを実行してよいか
+  ///
+  pub fn is_alert_commands_enabled(&self) -> bool {
+    self.settings.lock().unwrap().alert_commands_enabled
+  }
+
+  ///
+  /// ## アラートの送信先の Webhook を取得
+  ///
+  pub fn get_webhooks(&self) -> Vec<WebhookTarget> {
+    self.settings.lock().unwrap().webhooks.clone()
+  }
+
+  ///
+  /// ## 異常検知の設定を取得（サンプリングのループから参照）
+  ///
+  pub fn get_anomaly_detectors(&self) -> Vec<AnomalyDetectorSettings> {
+    self.settings.lock().unwrap().anomaly_detectors.clone()
+  }
+
+  ///
+  /// ## Prometheus のエンドポイントの設定を取得
+  ///
+  pub fn get_prometheus_settings(&self) -> PrometheusSettings {
+    self.settings.lock().unwrap().prometheus.clone()
+  }
+
+  ///
+  /// ## InfluxDB への送信の設定を取得（送信のたびに参照）
+  ///
+  pub fn get_influx_settings(&self) -> InfluxSettings {
+    self.settings.lock().unwrap().influx.clone()
+  }
+
+  ///
+  /// ## OpenTelemetry のコレクターへの送信の設定を取得（送信のたびに参照）
+  ///
+  pub fn get_otlp_settings(&self) -> OtlpSettings {
+    self.settings.lock().unwrap().otlp.clone()
+  }
+
+  ///
+  /// ## メンテナンス期間を取得（サンプリングのループから参照）
+  ///
+  pub fn get_maintenance_windows(&self) -> Vec<MaintenanceWindow> {
+    self.settings.lock().unwrap().maintenance_windows.clone()
+  }
+
+  ///
+  /// ## 有効な一時的な通知の停止を取得
+  ///
+  /// - param now: `i64` 現在時刻（UNIX エポックからのミリ秒）
+  ///
+  pub fn get_silences(&self, now: i64) -> Vec<Silence> {
+    self
+      .settings
+      .lock()
+      .unwrap()
+      .silences
+      .iter()
+      .filter(|silence| silence.is_active(now))
+      .cloned()
+      .collect()
+  }
 }
 
 pub mod commands {
@@ -295,4 +598,629 @@ pub mod commands {
 
     Ok(())
   }
+
+  #[tauri::command]
+  pub async fn get_process_watches(
+    state: tauri::State<'_, AppState>,
+  ) -> Result<Vec<ProcessWatchRule>, String> {
+    Ok(state.get_process_watches())
+  }
+
+  ///
+  /// ## プロセスの監視ルールを追加
+  ///
+  /// - `id` が空の場合は自動で採番する
+  ///
+  #[tauri::command]
+  pub async fn add_process_watch(
+    window: Window,
+    state: tauri::State<'_, AppState>,
+    mut rule: ProcessWatchRule,
+  ) -> Result<ProcessWatchRule, String> {
+    // 入力値の誤りは設定ファイルの破損ではないため、エラーイベントは発生させない
+    rule.validate()?;
+
+    let mut settings = state.settings.lock().unwrap();
+
+    if rule.id.is_empty() {
+      rule.id = format!("watch-{}", chrono::Utc::now().timestamp_millis());
+    }
+
+    if settings
+      .process_watches
+      .iter()
+      .any(|watch| watch.id == rule.id)
+    {
+      return Err(format!("Watch rule already exists: {}", rule.id));
+    }
+
+    if let Err(e) = settings.add_process_watch(rule.clone()) {
+      emit_error(&window)?;
+      return Err(e);
+    }
+
+    Ok(rule)
+  }
+
+  #[tauri::command]
+  pub async fn remove_process_watch(
+    window: Window,
+    state: tauri::State<'_, AppState>,
+    id: String,
+  ) -> Result<(), String> {
+    let mut settings = state.settings.lock().unwrap();
+
+    // 画面に残っていた古い ID などは設定ファイルの破損ではないため、エラーイベントは発生させない
+    if !settings.process_watches.iter().any(|watch| watch.id == id) {
+      return Err(format!("Watch rule not found: {}", id));
+    }
+
+    if let Err(e) = settings.remove_process_watch(&id) {
+      emit_error(&window)?;
+      return Err(e);
+    }
+
+    Ok(())
+  }
+
+  #[tauri::command]
+  pub async fn set_memory_leak_settings(
+    window: Window,
+    state: tauri::State<'_, AppState>,
+    new_settings: MemoryLeakSettings,
+  ) -> Result<(), String> {
+    // 入力値の誤りは設定ファイルの破損ではないため、エラーイベントは発生させない
+    new_settings.validate()?;
+
+    let mut settings = state.settings.lock().unwrap();
+
+    if let Err(e) = settings.set_memory_leak(new_settings) {
+      emit_error(&window)?;
+      return Err(e);
+    }
+
+    Ok(())
+  }
+
+  #[tauri::command]
+  pub async fn set_process_cpu_mode(
+    window: Window,
+    state: tauri::State<'_, AppState>,
+    new_mode: ProcessCpuMode,
+  ) -> Result<(), String> {
+    let mut settings = state.settings.lock().unwrap();
+
+    if let Err(e) = settings.set_process_cpu_mode(new_mode) {
+      emit_error(&window)?;
+      return Err(e);
+    }
+
+    Ok(())
+  }
+
+  #[tauri::command]
+  pub async fn get_alert_rules(
+    state: tauri::State<'_, AppState>,
+  ) -> Result<Vec<AlertRule>, String> {
+    Ok(state.get_alert_rules())
+  }
+
+  ///
+  /// ## アラートのルールを追加
+  ///
+  /// - `id` が空の場合は自動で採番する
+  ///
+  #[tauri::command]
+  pub async fn add_alert_rule(
+    window: Window,
+    state: tauri::State<'_, AppState>,
+    mut rule: AlertRule,
+  ) -> Result<AlertRule, String> {
+    // 入力値の誤りは設定ファイルの破損ではないため、エラーイベントは発生させない
+    rule.validate()?;
+
+    let mut settings = state.settings.lock().unwrap();
+
+    if rule.id.is_empty() {
+      rule.id = format!("alert-{}", chrono::Utc::now().timestamp_millis());
+    }
+
+    if settings
+      .alert_rules
+      .iter()
+      .any(|alert_rule| alert_rule.id == rule.id)
+    {
+      return Err(format!("Alert rule already exists: {}", rule.id));
+    }
+
+    if let Err(e) = settings.add_alert_rule(rule.clone()) {
+      emit_error(&window)?;
+      return Err(e);
+    }
+
+    Ok(rule)
+  }
+
+  #[tauri::command]
+  pub async fn update_alert_rule(
+    window: Window,
+    state: tauri::State<'_, AppState>,
+    rule: AlertRule,
+  ) -> Result<(), String> {
+    rule.validate()?;
+
+    let mut settings = state.settings.lock().unwrap();
+
+    // 画面に残っていた古い ID などは設定ファイルの破損ではないため、エラーイベントは発生させない
+    if !settings
+      .alert_rules
+      .iter()
+      .any(|alert_rule| alert_rule.id == rule.id)
+    {
+      return Err(format!("Alert rule not found: {}", rule.id));
+    }
+
+    if let Err(e) = settings.update_alert_rule(rule) {
+      emit_error(&window)?;
+      return Err(e);
+    }
+
+    Ok(())
+  }
+
+  #[tauri::command]
+  pub async fn remove_alert_rule(
+    window: Window,
+    state: tauri::State<'_, AppState>,
+    id: String,
+  ) -> Result<(), String> {
+    let mut settings = state.settings.lock().unwrap();
+
+    if !settings
+      .alert_rules
+      .iter()
+      .any(|alert_rule| alert_rule.id == id)
+    {
+      return Err(format!("Alert rule not found: {}", id));
+    }
+
+    if let Err(e) = settings.remove_alert_rule(&id) {
+      emit_error(&window)?;
+      return Err(e);
+    }
+
+    Ok(())
+  }
+
+  ///
+  /// ## アラートの発生時のコマンド実行を許可・禁止する
+  ///
+  #[tauri::command]
+  pub async fn set_alert_commands_enabled(
+    window: Window,
+    state: tauri::State<'_, AppState>,
+    enabled: bool,
+  ) -> Result<(), String> {
+    let mut settings = state.settings.lock().unwrap();
+
+    if let Err(e) = settings.set_alert_commands_enabled(enabled) {
+      emit_error(&window)?;
+      return Err(e);
+    }
+
+    Ok(())
+  }
+
+  #[tauri::command]
+  pub async fn get_webhooks(
+    state: tauri::State<'_, AppState>,
+  ) -> Result<Vec<WebhookTarget>, String> {
+    Ok(state.get_webhooks())
+  }
+
+  ///
+  /// ## Webhook を追加
+  ///
+  /// - `id` が空の場合は自動で採番する
+  ///
+  #[tauri::command]
+  pub async fn add_webhook(
+    window: Window,
+    state: tauri::State<'_, AppState>,
+    mut webhook: WebhookTarget,
+  ) -> Result<WebhookTarget, String> {
+    webhook.validate()?;
+
+    let mut settings = state.settings.lock().unwrap();
+
+    if webhook.id.is_empty() {
+      webhook.id = format!("webhook-{}", chrono::Utc::now().timestamp_millis());
+    }
+
+    if settings
+      .webhooks
+      .iter()
+      .any(|target| target.id == webhook.id)
+    {
+      return Err(format!("Webhook already exists: {}", webhook.id));
+    }
+
+    if let Err(e) = settings.add_webhook(webhook.clone()) {
+      emit_error(&window)?;
+      return Err(e);
+    }
+
+    Ok(webhook)
+  }
+
+  #[tauri::command]
+  pub async fn update_webhook(
+    window: Window,
+    state: tauri::State<'_, AppState>,
+    webhook: WebhookTarget,
+  ) -> Result<(), String> {
+    webhook.validate()?;
+
+    let mut settings = state.settings.lock().unwrap();
+
+    // 画面に残っていた古い ID などは設定ファイルの破損ではないため、エラーイベントは発生させない
+    if !settings
+      .webhooks
+      .iter()
+      .any(|target| target.id == webhook.id)
+    {
+      return Err(format!("Webhook not found: {}", webhook.id));
+    }
+
+    if let Err(e) = settings.update_webhook(webhook) {
+      emit_error(&window)?;
+      return Err(e);
+    }
+
+    Ok(())
+  }
+
+  #[tauri::command]
+  pub async fn remove_webhook(
+    window: Window,
+    state: tauri::State<'_, AppState>,
+    id: String,
+  ) -> Result<(), String> {
+    let mut settings = state.settings.lock().unwrap();
+
+    if !settings.webhooks.iter().any(|target| target.id == id) {
+      return Err(format!("Webhook not found: {}", id));
+    }
+
+    if let Err(e) = settings.remove_webhook(&id) {
+      emit_error(&window)?;
+      return Err(e);
+    }
+
+    Ok(())
+  }
+
+  #[tauri::command]
+  pub async fn get_anomaly_detectors(
+    state: tauri::State<'_, AppState>,
+  ) -> Result<Vec<AnomalyDetectorSettings>, String> {
+    Ok(state.get_anomaly_detectors())
+  }
+
+  ///
+  /// ## 指標の異常検知を設定
+  ///
+  /// - 同じ指標の設定がある場合は置き換える
+  ///
+  #[tauri::command]
+  pub async fn set_anomaly_detector(
+    window: Window,
+    state: tauri::State<'_, AppState>,
+    detector: AnomalyDetectorSettings,
+  ) -> Result<(), String> {
+    detector.validate()?;
+
+    let mut settings = state.settings.lock().unwrap();
+
+    if let Err(e) = settings.set_anomaly_detector(detector) {
+      emit_error(&window)?;
+      return Err(e);
+    }
+
+    Ok(())
+  }
+
+  #[tauri::command]
+  pub async fn remove_anomaly_detector(
+    window: Window,
+    state: tauri::State<'_, AppState>,
+    metric: MetricKind,
+  ) -> Result<(), String> {
+    let mut settings = state.settings.lock().unwrap();
+
+    // 画面に残っていた古い設定などは設定ファイルの破損ではないため、エラーイベントは発生させない
+    if !settings
+      .anomaly_detectors
+      .iter()
+      .any(|detector| detector.metric == metric)
+    {
+      return Err(format!("Anomaly detector not found: {:?}", metric));
+    }
+
+    if let Err(e) = settings.remove_anomaly_detector(metric) {
+      emit_error(&window)?;
+      return Err(e);
+    }
+
+    Ok(())
+  }
+
+  #[tauri::command]
+  pub async fn get_maintenance_windows(
+    state: tauri::State<'_, AppState>,
+  ) -> Result<Vec<MaintenanceWindow>, String> {
+    Ok(state.get_maintenance_windows())
+  }
+
+  ///
+  /// ## メンテナンス期間を追加
+  ///
+  /// - `id` が空の場合は自動で採番する
+  ///
+  #[tauri::command]
+  pub async fn add_maintenance_window(
+    window: Window,
+    state: tauri::State<'_, AppState>,
+    mut maintenance_window: MaintenanceWindow,
+  ) -> Result<MaintenanceWindow, String> {
+    maintenance_window.validate()?;
+
+    let mut settings = state.settings.lock().unwrap();
+
+    if maintenance_window.id.is_empty() {
+      maintenance_window.id =
+        format!("maintenance-{}", chrono::Utc::now().timestamp_millis());
+    }
+
+    if settings
+      .maintenance_windows
+      .iter()
+      .any(|current| current.id == maintenance_window.id)
+    {
+      return Err(format!(
+        "Maintenance window already exists: {}",
+        maintenance_window.id
+      ));
+    }
+
+    if let Err(e) = settings.add_maintenance_window(maintenance_window.clone()) {
+      emit_error(&window)?;
+      return Err(e);
+    }
+
+    Ok(maintenance_window)
+  }
+
+  #[tauri::command]
+  pub async fn update_maintenance_window(
+    window: Window,
+    state: tauri::State<'_, AppState>,
+    maintenance_window: MaintenanceWindow,
+  ) -> Result<(), String> {
+    maintenance_window.validate()?;
+
+    let mut settings = state.settings.lock().unwrap();
+
+    // 画面に残っていた古い ID などは設定ファイルの破損ではないため、エラーイベントは発生させない
+    if !settings
+      .maintenance_windows
+      .iter()
+      .any(|current| current.id == maintenance_window.id)
+    {
+      return Err(format!(
+        "Maintenance window not found: {}",
+        maintenance_window.id
+      ));
+    }
+
+    if let Err(e) = settings.update_maintenance_window(maintenance_window) {
+      emit_error(&window)?;
+      return Err(e);
+    }
+
+    Ok(())
+  }
+
+  #[tauri::command]
+  pub async fn remove_maintenance_window(
+    window: Window,
+    state: tauri::State<'_, AppState>,
+    id: String,
+  ) -> Result<(), String> {
+    let mut settings = state.settings.lock().unwrap();
+
+    if !settings
+      .maintenance_windows
+      .iter()
+      .any(|current| current.id == id)
+    {
+      return Err(format!("Maintenance window not found: {}", id));
+    }
+
+    if let Err(e) = settings.remove_maintenance_window(&id) {
+      emit_error(&window)?;
+      return Err(e);
+    }
+
+    Ok(())
+  }
+
+  #[tauri::command]
+  pub async fn get_silences(
+    state: tauri::State<'_, AppState>,
+  ) -> Result<Vec<Silence>, String> {
+    Ok(state.get_silences(chrono::Utc::now().timestamp_millis()))
+  }
+
+  ///
+  /// ## 指定した時間だけアラートを通知しない
+  ///
+  /// - param rule_id: `Option<String>` 対象のルール（`None` の場合はすべてのルール）
+  /// - param minutes: `u64` 通知しない時間（分、最大7日間）
+  ///
+  #[tauri::command]
+  pub async fn silence_alerts(
+    window: Window,
+    state: tauri::State<'_, AppState>,
+    rule_id: Option<String>,
+    minutes: u64,
+  ) -> Result<Silence, String> {
+    let silence = Silence::new(rule_id, chrono::Utc::now().timestamp_millis(), minutes)?;
+
+    let mut settings = state.settings.lock().unwrap();
+
+    if let Some(rule_id) = &silence.rule_id {
+      if !settings.alert_rules.iter().any(|rule| &rule.id == rule_id) {
+        return Err(format!("Alert rule not found: {}", rule_id));
+      }
+    }
+
+    if let Err(e) = settings.add_silence(silence.clone()) {
+      emit_error(&window)?;
+      return Err(e);
+    }
+
+    Ok(silence)
+  }
+
+  ///
+  /// ## 一時的な通知の停止を解除
+  ///
+  #[tauri::command]
+  pub async fn remove_silence(
+    window: Window,
+    state: tauri::State<'_, AppState>,
+    id: String,
+  ) -> Result<(), String> {
+    let mut settings = state.settings.lock().unwrap();
+
+    // 期限切れで削除済みの ID などは設定ファイルの破損ではないため、エラーイベントは発生させない
+    if !settings.silences.iter().any(|silence| silence.id == id) {
+      return Err(format!("Silence not found: {}", id));
+    }
+
+    if let Err(e) = settings.remove_silence(&id) {
+      emit_error(&window)?;
+      return Err(e);
+    }
+
+    Ok(())
+  }
+
+  #[tauri::command]
+  pub async fn get_prometheus_settings(
+    state: tauri::State<'_, AppState>,
+  ) -> Result<PrometheusSettings, String> {
+    Ok(state.get_prometheus_settings())
+  }
+
+  ///
+  /// ## Prometheus のエンドポイントの設定を更新
+  ///
+  /// - 待ち受けに失敗した場合は設定を保存しない
+  ///
+  #[tauri::command]
+  pub async fn set_prometheus_settings(
+    window: Window,
+    state: tauri::State<'_, AppState>,
+    exporter: tauri::State<'_, PrometheusExporter>,
+    new_settings: PrometheusSettings,
+  ) -> Result<(), String> {
+    new_settings.socket_addr()?;
+    exporter.apply(&new_settings).await?;
+
+    let mut settings = state.settings.lock().unwrap();
+
+    if let Err(e) = settings.set_prometheus(new_settings) {
+      emit_error(&window)?;
+      return Err(e);
+    }
+
+    Ok(())
+  }
+
+  #[tauri::command]
+  pub async fn get_influx_settings(
+    state: tauri::State<'_, AppState>,
+  ) -> Result<InfluxSettings, String> {
+    Ok(state.get_influx_settings())
+  }
+
+  #[tauri::command]
+  pub async fn set_influx_settings(
+    window: Window,
+    state: tauri::State<'_, AppState>,
+    new_settings: InfluxSettings,
+  ) -> Result<(), String> {
+    new_settings.validate()?;
+
+    let mut settings = state.settings.lock().unwrap();
+
+    if let Err(e) = settings.set_influx(new_settings) {
+      emit_error(&window)?;
+      return Err(e);
+    }
+
+    Ok(())
+  }
+
+  ///
+  /// ## InfluxDB への接続を確認（保存前の設定でテスト用の 1行を送信）
+  ///
+  #[tauri::command]
+  pub async fn test_influx_connection(settings: InfluxSettings) -> Result<(), String> {
+    settings.validate()?;
+
+    let sample = MetricSample {
+      timestamp: chrono::Utc::now().timestamp_millis(),
+      values: [(MetricKind::CpuUsage, 0.0)].into_iter().collect(),
+    };
+    let line =
+      influx_service::to_line(&sample, &settings.measurement, &settings.resolved_tags())
+        .ok_or_else(|| "Failed to build test line".to_string())?;
+
+    influx_service::send_batch(&reqwest::Client::new(), &settings, &[line])
+      .await
+      .map_err(|e| e.message)
+  }
+
+  #[tauri::command]
+  pub async fn get_otlp_settings(
+    state: tauri::State<'_, AppState>,
+  ) -> Result<OtlpSettings, String> {
+    Ok(state.get_otlp_settings())
+  }
+
+  ///
+  /// ## OpenTelemetry のコレクターへの送信の設定を更新
+  ///
+  /// - 送信の間隔は次回の送信から反映する
+  ///
+  #[tauri::command]
+  pub async fn set_otlp_settings(
+    window: Window,
+    state: tauri::State<'_, AppState>,
+    new_settings: OtlpSettings,
+  ) -> Result<(), String> {
+    new_settings.validate()?;
+
+    let mut settings = state.settings.lock().unwrap();
+
+    if let Err(e) = settings.set_otlp(new_settings) {
+      emit_error(&window)?;
+      return Err(e);
+    }
+
+    Ok(())
+  }
 }
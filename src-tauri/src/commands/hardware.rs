@@ -1,14 +1,43 @@
+use crate::commands::config;
+use crate::enums::metric::MetricKind;
+use crate::enums::process::{
+  ProcessCpuMode, ProcessGroupBy, ProcessSortKey, SortDirection,
+};
+use crate::services::alert_command_service;
+use crate::services::alert_history_service::AlertHistory;
+use crate::services::alert_service::{AlertEngine, AlertEventKind, SystemClock};
+use crate::services::anomaly_service::{self, AnomalyDetector, AnomalyMarker};
+use crate::services::export_service::{self, ExportFormat};
 use crate::services::graphic_service;
+use crate::services::influx_service::{self, DiskBuffer};
+use crate::services::memory_leak_service::{self, MemoryLeakDetector};
+use crate::services::metric_history_service::{
+  MetricHistory, MetricSample, METRIC_HISTORY_CAPACITY,
+};
+use crate::services::otlp_service;
+use crate::services::process_group_service::{self, ProcessGroupHistory};
+use crate::services::process_service;
+use crate::services::process_watch_service::{self, ProcessLifecycleTracker};
+use crate::services::silence_service;
 use crate::services::system_info_service;
+use crate::services::thread_service::ThreadSampler;
+use crate::services::webhook_service::{self, WebhookDeliveryLog};
+use crate::utils;
+use crate::utils::formatter;
 use crate::{log_debug, log_error, log_info, log_internal, log_warn};
-use serde::{Serialize, Serializer};
+use regex::Regex;
+use serde::{Deserialize, Serialize, Serializer};
+use std::cmp::Ordering;
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::collections::VecDeque;
 use std::sync::{Arc, Mutex};
 use std::thread;
-use std::time::Duration;
-use sysinfo::{Pid, ProcessesToUpdate, System};
-use tauri::command;
+use std::time::{Duration, Instant};
+use sysinfo::{Pid, ProcessesToUpdate, System, Users};
+use tauri::{command, AppHandle, Emitter, Manager};
+use tauri_plugin_dialog::DialogExt;
+use tauri_plugin_notification::NotificationExt;
 
 pub struct AppState {
   pub system: Arc<Mutex<System>>,
@@ -18,6 +47,17 @@ pub struct AppState {
   pub gpu_usage: Arc<Mutex<f32>>,
   pub process_cpu_histories: Arc<Mutex<HashMap<Pid, VecDeque<f32>>>>,
   pub process_memory_histories: Arc<Mutex<HashMap<Pid, VecDeque<f32>>>>,
+  pub process_disk_read_histories: Arc<Mutex<HashMap<Pid, VecDeque<f32>>>>,
+  pub process_disk_write_histories: Arc<Mutex<HashMap<Pid, VecDeque<f32>>>>,
+  pub process_sample_timestamps: Arc<Mutex<HashMap<Pid, VecDeque<i64>>>>,
+  pub memory_leak_detector: Arc<Mutex<MemoryLeakDetector>>,
+  pub thread_sampler: Arc<Mutex<ThreadSampler>>,
+  pub alert_history: Arc<Mutex<AlertHistory>>,
+  pub webhook_delivery_log: Arc<Mutex<WebhookDeliveryLog>>,
+  pub metric_history: Arc<Mutex<MetricHistory>>,
+  pub anomaly_detector: Arc<Mutex<AnomalyDetector>>,
+  pub process_group_histories:
+    Arc<Mutex<HashMap<(ProcessGroupBy, String), ProcessGroupHistory>>>,
 }
 
 ///
@@ -34,11 +74,21 @@ const HISTORY_CAPACITY: usize = 60;
 #[serde(rename_all = "camelCase")]
 pub struct ProcessInfo {
   pub pid: i32,
+  pub parent_pid: Option<i32>,
   pub name: String,
+  pub user: Option<String>,
+  pub start_time: u64,
   #[serde(serialize_with = "serialize_usage")]
   pub cpu_usage: f32,
   #[serde(serialize_with = "serialize_usage")]
   pub memory_usage: f32,
+  pub memory: process_service::ProcessMemoryBreakdown,
+  #[serde(serialize_with = "serialize_usage")]
+  pub disk_read_bytes_per_sec: f32,
+  #[serde(serialize_with = "serialize_usage")]
+  pub disk_write_bytes_per_sec: f32,
+  pub priority: Option<process_service::ProcessPriority>, // ページングで残ったプロセスのみ取得する
+  pub cpu_affinity: Option<Vec<usize>>,
 }
 
 fn serialize_usage<S>(x: &f32, s: S) -> Result<S::Ok, S::Error>
@@ -52,20 +102,89 @@ where
   }
 }
 
+#[derive(Debug, Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ProcessQuery {
+  pub sort_key: Option<ProcessSortKey>,
+  pub sort_direction: SortDirection,
+  pub name: Option<String>, // 部分一致（大文字小文字を区別しない）
+  pub name_regex: Option<String>, // 正規表現
+  pub user: Option<String>,
+  pub min_cpu_usage: Option<f32>,
+  pub min_memory_usage: Option<f32>,
+  pub top: Option<usize>, // ソート後の上位 N 件に絞り込む
+  pub offset: Option<usize>,
+  pub limit: Option<usize>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessListResponse {
+  pub total: usize, // 条件に一致した件数（`top` とページングの適用前）
+  pub processes: Vec<ProcessInfo>,
+}
+
 ///
 /// ## プロセスリストを取得
 ///
+/// - param query: `Option<ProcessQuery>` ソート・絞り込み・ページングの条件
+/// - CPU 使用率は設定（`ProcessCpuMode`）に従って 1コア基準またはマシン全体の基準で返す
+///
+#[command]
+pub fn get_process_list(
+  state: tauri::State<'_, AppState>,
+  config_state: tauri::State<'_, config::AppState>,
+  query: Option<ProcessQuery>,
+) -> Result<ProcessListResponse, String> {
+  let query = query.unwrap_or_default();
+  let cpu_mode = config_state.get_process_cpu_mode();
+  let (total, mut processes) =
+    apply_process_query(collect_process_infos(&state, cpu_mode), &query)?;
+
+  // 優先度などの取得はコストが高いため、ページングで残ったプロセスのみ取得する
+  fill_scheduling(&mut processes);
+
+  Ok(ProcessListResponse { total, processes })
+}
+
+///
+/// ## プロセスの親子関係をツリー形式で取得
+///
+/// - 各ノードには自身と子孫プロセスの CPU / メモリ使用量の合計を含む
+/// - 全プロセスの `/proc/<pid>` を読むとコストが高いため、メモリ使用量の内訳は `sysinfo` から取得できる値のみ（詳細は `get_process_details` で取得する）
+///
 #[command]
-pub fn get_process_list(state: tauri::State<'_, AppState>) -> Vec<ProcessInfo> {
+pub fn get_process_tree(
+  state: tauri::State<'_, AppState>,
+  config_state: tauri::State<'_, config::AppState>,
+) -> Vec<ProcessTreeNode> {
+  let cpu_mode = config_state.get_process_cpu_mode();
+
+  build_process_tree(collect_process_infos(&state, cpu_mode))
+}
+
+///
+/// ## 全プロセスの `ProcessInfo` を収集
+///
+/// - スレッドのエントリは除外する（ツリーの合計で CPU / メモリを二重に数えないため）
+/// - param cpu_mode: `ProcessCpuMode` CPU 使用率の基準
+///
+fn collect_process_infos(state: &AppState, cpu_mode: ProcessCpuMode) -> Vec<ProcessInfo> {
   let mut system = state.system.lock().unwrap();
   let process_cpu_histories = state.process_cpu_histories.lock().unwrap();
   let process_memory_histories = state.process_memory_histories.lock().unwrap();
+  let process_disk_read_histories = state.process_disk_read_histories.lock().unwrap();
+  let process_disk_write_histories = state.process_disk_write_histories.lock().unwrap();
 
   system.refresh_processes(ProcessesToUpdate::All, true);
 
+  let users = Users::new_with_refreshed_list();
+  let cpu_scale = cpu_mode.scale(system.cpus().len());
+
   system
     .processes()
     .values()
+    .filter(|process| !process_service::is_thread(process))
     .map(|process| {
       let pid = process.pid();
 
@@ -73,7 +192,7 @@ pub fn get_process_list(state: tauri::State<'_, AppState>) -> Vec<ProcessInfo> {
       let cpu_usage = if let Some(history) = process_cpu_histories.get(&pid) {
         let len = history.len().min(5); // 最大5秒分のデータ
         let sum: f32 = history.iter().rev().take(len).sum();
-        let avg = sum / len as f32;
+        let avg = sum / len as f32 * cpu_scale;
 
         (avg * 10.0).round() / 10.0
       } else {
@@ -88,19 +207,266 @@ pub fn get_process_list(state: tauri::State<'_, AppState>) -> Vec<ProcessInfo> {
 
         (avg * 10.0).round() / 10.0
       } else {
-        process.memory() as f32 / 1024.0 // 履歴がなければ現在のメモリ使用量を返す
+        formatter::bytes_to_mb(process.memory()) // 履歴がなければ現在のメモリ使用量を返す
       };
 
+      // 5秒間のディスク読み書き速度（bytes/s）の平均を計算
+      let disk_read_bytes_per_sec =
+        average_recent(process_disk_read_histories.get(&pid)).unwrap_or(0.0);
+      let disk_write_bytes_per_sec =
+        average_recent(process_disk_write_histories.get(&pid)).unwrap_or(0.0);
+
+      // 内訳の詳細は `fill_memory_breakdowns` で補完する
+      let memory = process_service::ProcessMemoryBreakdown::from_process(process);
+
+      let user = process
+        .user_id()
+        .and_then(|uid| users.get_user_by_id(uid))
+        .map(|user| user.name().to_string());
+
       ProcessInfo {
         pid: pid.as_u32() as i32,                            // プロセスID
+        parent_pid: process.parent().map(pid_to_i32),        // 親プロセスID
         name: process.name().to_string_lossy().into_owned(), // プロセス名を取得
+        user,                                                // 実行ユーザー名
+        start_time: process.start_time(),                    // PID 再利用の判別に使用
         cpu_usage,                                           // 平均CPU使用率
         memory_usage,                                        // 平均メモリ使用率
+        memory,                                              // メモリ使用量の内訳
+        disk_read_bytes_per_sec,                             // 平均ディスク読み込み速度
+        disk_write_bytes_per_sec,                            // 平均ディスク書き込み速度
+        priority: None, // `fill_scheduling` で補完する
+        cpu_affinity: None,
       }
     })
     .collect()
 }
 
+///
+/// ## 直近5秒間の履歴の平均を計算
+///
+/// - 履歴がない場合は `None` を返す
+///
+pub(crate) fn average_recent(history: Option<&VecDeque<f32>>) -> Option<f32> {
+  let history = history.filter(|history| !history.is_empty())?;
+
+  let len = history.len().min(5); // 最大5秒分のデータ
+  let sum: f32 = history.iter().rev().take(len).sum();
+  let avg = sum / len as f32;
+
+  Some((avg * 10.0).round() / 10.0)
+}
+
+///
+/// ## 優先度と CPU アフィニティを取得して設定
+///
+/// - 権限不足で取得できない場合は `None` のまま
+///
+fn fill_scheduling(processes: &mut [ProcessInfo]) {
+  for process in processes.iter_mut() {
+    let (priority, cpu_affinity) =
+      process_service::read_scheduling(Pid::from_u32(process.pid as u32));
+    process.priority = priority;
+    process.cpu_affinity = cpu_affinity;
+  }
+}
+
+///
+/// ## `/proc/<pid>` からメモリ使用量の内訳を取得して設定
+///
+fn fill_memory_breakdowns(processes: &mut [ProcessInfo]) {
+  for process in processes.iter_mut() {
+    process_service::fill_memory_breakdown(process.pid as u32, &mut process.memory);
+  }
+}
+
+///
+/// ## クエリに従ってプロセスリストを絞り込み・ソート・ページング
+///
+/// - return: `(usize, Vec<ProcessInfo>)` 条件に一致した件数と結果
+/// - メモリ内訳の取得はコストが高いため、ページングで残ったプロセスのみ取得する
+/// - ただし内訳でソートする場合は、絞り込み後のプロセスすべてで取得する（ソートの前に絞り込むのはこのため）
+///
+fn apply_process_query(
+  processes: Vec<ProcessInfo>,
+  query: &ProcessQuery,
+) -> Result<(usize, Vec<ProcessInfo>), String> {
+  let name_regex = match &query.name_regex {
+    Some(pattern) => {
+      Some(Regex::new(pattern).map_err(|e| format!("Invalid name regex: {}", e))?)
+    }
+    None => None,
+  };
+  let name = query.name.as_ref().map(|name| name.to_lowercase());
+
+  let mut processes: Vec<ProcessInfo> = processes
+    .into_iter()
+    .filter(|process| {
+      name
+        .as_ref()
+        .map_or(true, |name| process.name.to_lowercase().contains(name))
+        && name_regex
+          .as_ref()
+          .map_or(true, |regex| regex.is_match(&process.name))
+        && query
+          .user
+          .as_ref()
+          .map_or(true, |user| process.user.as_ref() == Some(user))
+        && query
+          .min_cpu_usage
+          .map_or(true, |min| process.cpu_usage >= min)
+        && query
+          .min_memory_usage
+          .map_or(true, |min| process.memory_usage >= min)
+    })
+    .collect();
+
+  let needs_breakdown = query
+    .sort_key
+    .map_or(false, |sort_key| sort_key.requires_memory_breakdown());
+  if needs_breakdown {
+    fill_memory_breakdowns(&mut processes);
+  }
+
+  // ページングの結果が呼び出しごとに変わらないよう、同順位（ソート指定なしを含む）は PID 順にする
+  processes.sort_by(|a, b| {
+    let ordering = match query.sort_key {
+      Some(sort_key) => match query.sort_direction {
+        SortDirection::Ascending => compare_process(a, b, sort_key),
+        SortDirection::Descending => compare_process(a, b, sort_key).reverse(),
+      },
+      None => Ordering::Equal,
+    };
+    ordering.then_with(|| a.pid.cmp(&b.pid))
+  });
+
+  let total = processes.len();
+
+  if let Some(top) = query.top {
+    processes.truncate(top);
+  }
+
+  let mut processes: Vec<ProcessInfo> = processes
+    .into_iter()
+    .skip(query.offset.unwrap_or(0))
+    .take(query.limit.unwrap_or(usize::MAX))
+    .collect();
+
+  if !needs_breakdown {
+    fill_memory_breakdowns(&mut processes);
+  }
+
+  Ok((total, processes))
+}
+
+fn compare_process(a: &ProcessInfo, b: &ProcessInfo, key: ProcessSortKey) -> Ordering {
+  match key {
+    ProcessSortKey::Pid => a.pid.cmp(&b.pid),
+    ProcessSortKey::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+    ProcessSortKey::User => a.user.cmp(&b.user),
+    ProcessSortKey::StartTime => a.start_time.cmp(&b.start_time),
+    ProcessSortKey::CpuUsage => compare_f32(a.cpu_usage, b.cpu_usage),
+    ProcessSortKey::MemoryUsage => compare_f32(a.memory_usage, b.memory_usage),
+    ProcessSortKey::Rss => a.memory.rss.cmp(&b.memory.rss),
+    ProcessSortKey::VirtualMemory => a.memory.virtual_size.cmp(&b.memory.virtual_size),
+    ProcessSortKey::SharedMemory => a.memory.shared.cmp(&b.memory.shared),
+    ProcessSortKey::PrivateMemory => a.memory.private.cmp(&b.memory.private),
+    ProcessSortKey::Swap => a.memory.swap.cmp(&b.memory.swap),
+    ProcessSortKey::Pss => a.memory.pss.cmp(&b.memory.pss),
+    ProcessSortKey::DiskRead => {
+      compare_f32(a.disk_read_bytes_per_sec, b.disk_read_bytes_per_sec)
+    }
+    ProcessSortKey::DiskWrite => {
+      compare_f32(a.disk_write_bytes_per_sec, b.disk_write_bytes_per_sec)
+    }
+  }
+}
+
+///
+/// ## 使用率などの値を比較（NaN は同順位として扱う）
+///
+fn compare_f32(a: f32, b: f32) -> Ordering {
+  a.partial_cmp(&b).unwrap_or(Ordering::Equal)
+}
+
+fn pid_to_i32(pid: Pid) -> i32 {
+  pid.as_u32() as i32
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessTreeNode {
+  #[serde(flatten)]
+  pub process: ProcessInfo,
+  #[serde(serialize_with = "serialize_usage")]
+  pub total_cpu_usage: f32,
+  #[serde(serialize_with = "serialize_usage")]
+  pub total_memory_usage: f32,
+  pub children: Vec<ProcessTreeNode>,
+}
+
+///
+/// ## プロセスリストからツリーを構築
+///
+/// - 親プロセスが一覧に存在しない場合はルートとして扱う
+///
+fn build_process_tree(processes: Vec<ProcessInfo>) -> Vec<ProcessTreeNode> {
+  let pids: HashSet<i32> = processes.iter().map(|process| process.pid).collect();
+
+  let mut children_map: HashMap<i32, Vec<ProcessInfo>> = HashMap::new();
+  let mut roots = Vec::new();
+
+  for process in processes {
+    match process.parent_pid {
+      Some(ppid) if ppid != process.pid && pids.contains(&ppid) => {
+        children_map.entry(ppid).or_default().push(process);
+      }
+      _ => roots.push(process),
+    }
+  }
+
+  let mut tree: Vec<ProcessTreeNode> = roots
+    .into_iter()
+    .map(|process| build_process_tree_node(process, &mut children_map))
+    .collect();
+
+  tree.sort_by_key(|node| node.process.pid);
+  tree
+}
+
+fn build_process_tree_node(
+  process: ProcessInfo,
+  children_map: &mut HashMap<i32, Vec<ProcessInfo>>,
+) -> ProcessTreeNode {
+  // 取り出してから再帰することで、循環していても同じプロセスを二度辿らない
+  let mut children: Vec<ProcessTreeNode> = children_map
+    .remove(&process.pid)
+    .unwrap_or_default()
+    .into_iter()
+    .map(|child| build_process_tree_node(child, children_map))
+    .collect();
+
+  children.sort_by_key(|node| node.process.pid);
+
+  let total_cpu_usage = process.cpu_usage
+    + children
+      .iter()
+      .map(|child| child.total_cpu_usage)
+      .sum::<f32>();
+  let total_memory_usage = process.memory_usage
+    + children
+      .iter()
+      .map(|child| child.total_memory_usage)
+      .sum::<f32>();
+
+  ProcessTreeNode {
+    total_cpu_usage: (total_cpu_usage * 10.0).round() / 10.0,
+    total_memory_usage: (total_memory_usage * 10.0).round() / 10.0,
+    process,
+    children,
+  }
+}
+
 ///
 /// ## CPU使用率（%）を取得
 ///
@@ -246,14 +612,164 @@ pub fn get_gpu_usage_history(
   history.iter().rev().take(seconds).cloned().collect()
 }
 
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetricHistoryPoint {
+  pub timestamp: i64, // UNIX エポックからのミリ秒
+  pub value: f32,
+  pub anomaly: Option<AnomalyMarker>, // 異常検知で基準から外れたと判定したサンプル
+}
+
+///
+/// ## システム全体の指標の履歴を取得
+///
+/// - param metric: `MetricKind` 取得する指標
+/// - param seconds: `u64` 取得する秒数（最大1時間）
+/// - return: `Vec<MetricHistoryPoint>` 計測時刻と異常のマーカーつきの履歴（古い順）
+/// - GPU の指標は、アラートや異常検知で使用している間のみ記録する
+///
+#[command]
+pub fn get_metric_history(
+  state: tauri::State<'_, AppState>,
+  metric: MetricKind,
+  seconds: u64,
+) -> Vec<MetricHistoryPoint> {
+  let from = seconds_ago_millis(seconds.min(METRIC_HISTORY_CAPACITY as u64));
+
+  let points = state.metric_history.lock().unwrap().points(metric, from);
+  let markers: HashMap<i64, AnomalyMarker> = state
+    .anomaly_detector
+    .lock()
+    .unwrap()
+    .markers(Some(metric), Some(from))
+    .into_iter()
+    .map(|marker| (marker.timestamp, marker))
+    .collect();
+
+  points
+    .into_iter()
+    .map(|(timestamp, value)| MetricHistoryPoint {
+      timestamp,
+      value,
+      anomaly: markers.get(&timestamp).cloned(),
+    })
+    .collect()
+}
+
+///
+/// ## 異常検知で基準から外れたと判定したサンプルを取得
+///
+/// - param metric: `Option<MetricKind>` 指定した指標のみ取得
+/// - param seconds: `Option<u64>` 指定した秒数以内のみ取得
+/// - return: `Vec<AnomalyMarker>` 古い順
+///
+#[command]
+pub fn get_anomalies(
+  state: tauri::State<'_, AppState>,
+  metric: Option<MetricKind>,
+  seconds: Option<u64>,
+) -> Vec<AnomalyMarker> {
+  let from = seconds.map(seconds_ago_millis);
+
+  state.anomaly_detector.lock().unwrap().markers(metric, from)
+}
+
+///
+/// ## 指定した秒数前の時刻（UNIX エポックからのミリ秒）
+///
+/// - 大きな値を指定した場合は UNIX エポック以前まで遡らない
+///
+fn seconds_ago_millis(seconds: u64) -> i64 {
+  let millis = i64::try_from(seconds.saturating_mul(1000)).unwrap_or(i64::MAX);
+  chrono::Utc::now()
+    .timestamp_millis()
+    .saturating_sub(millis)
+    .max(0)
+}
+
+///
+/// ## 記録した指標の履歴をファイルに出力
+///
+/// - param metrics: `Vec<MetricKind>` 出力する指標（列の順番）
+/// - param from: `Option<i64>` 開始時刻（UNIX エポックからのミリ秒、`None` の場合は記録している最も古いサンプルから）
+/// - param to: `Option<i64>` 終了時刻（`None` の場合は最新のサンプルまで）
+/// - param format: `ExportFormat` CSV または NDJSON（計測時刻は ISO-8601 形式）
+/// - return: `Option<String>` 保存したファイルのパス（保存先の選択をキャンセルした場合は `None`）
+///
+#[command]
+pub async fn export_history(
+  app_handle: AppHandle,
+  state: tauri::State<'_, AppState>,
+  metrics: Vec<MetricKind>,
+  from: Option<i64>,
+  to: Option<i64>,
+  format: ExportFormat,
+) -> Result<Option<String>, String> {
+  if metrics.is_empty() {
+    return Err("Select at least one metric to export".to_string());
+  }
+
+  // 保存先の選択中に追加されたサンプルは含めない
+  let samples = state.metric_history.lock().unwrap().range(from, to);
+  let content = export_service::render(&samples, &metrics, format);
+
+  let file_name = format!(
+    "hardware-monitor-history-{}.{}",
+    chrono::Local::now().format("%Y%m%d-%H%M%S"),
+    format.extension()
+  );
+
+  // ダイアログの表示中にランタイムのスレッドを止めないよう、別スレッドで待つ
+  let path = tauri::async_runtime::spawn_blocking(move || {
+    app_handle
+      .dialog()
+      .file()
+      .add_filter(format.extension().to_uppercase(), &[format.extension()])
+      .set_file_name(file_name)
+      .blocking_save_file()
+  })
+  .await
+  .map_err(|e| e.to_string())?;
+
+  let path = match path {
+    Some(path) => path
+      .into_path()
+      .map_err(|e| format!("Invalid save location: {}", e))?,
+    None => return Ok(None),
+  };
+
+  std::fs::write(&path, content)
+    .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+
+  log_info!(
+    "exported",
+    "export_history",
+    Some(format!("{} samples to {}", samples.len(), path.display()))
+  );
+
+  Ok(Some(path.to_string_lossy().to_string()))
+}
+
+///
+/// ## 履歴に値を追加（`HISTORY_CAPACITY` を超えた分は古い順に削除）
+///
+fn push_history(history: &mut VecDeque<f32>, value: f32) {
+  if history.len() >= HISTORY_CAPACITY {
+    history.pop_front();
+  }
+  history.push_back(value);
+}
+
 ///
 /// ## システム情報の初期化
 ///
+/// - param app_handle: `AppHandle` イベントの通知と設定の参照に使用
 /// - param system: `Arc<Mutex<System>>` システム情報
 ///
 /// - `SYSTEM_INFO_INIT_INTERVAL` 秒ごとにCPU使用率とメモリ使用率を更新
 ///
 pub fn initialize_system(
+  app_handle: AppHandle,
   system: Arc<Mutex<System>>,
   cpu_history: Arc<Mutex<VecDeque<f32>>>,
   memory_history: Arc<Mutex<VecDeque<f32>>>,
@@ -261,8 +777,50 @@ pub fn initialize_system(
   gpu_history: Arc<Mutex<VecDeque<f32>>>,
   process_cpu_histories: Arc<Mutex<HashMap<Pid, VecDeque<f32>>>>,
   process_memory_histories: Arc<Mutex<HashMap<Pid, VecDeque<f32>>>>,
+  process_disk_read_histories: Arc<Mutex<HashMap<Pid, VecDeque<f32>>>>,
+  process_disk_write_histories: Arc<Mutex<HashMap<Pid, VecDeque<f32>>>>,
+  process_sample_timestamps: Arc<Mutex<HashMap<Pid, VecDeque<i64>>>>,
+  memory_leak_detector: Arc<Mutex<MemoryLeakDetector>>,
+  thread_sampler: Arc<Mutex<ThreadSampler>>,
+  alert_history: Arc<Mutex<AlertHistory>>,
+  webhook_delivery_log: Arc<Mutex<WebhookDeliveryLog>>,
+  metric_history: Arc<Mutex<MetricHistory>>,
+  anomaly_detector: Arc<Mutex<AnomalyDetector>>,
+  process_group_histories: Arc<
+    Mutex<HashMap<(ProcessGroupBy, String), ProcessGroupHistory>>,
+  >,
 ) {
+  // 前回計測時のプロセスごとの累積読み書きバイト数
+  let mut last_disk_totals: HashMap<Pid, (u64, u64)> = HashMap::new();
+  let mut last_sampled_at = Instant::now();
+  let mut lifecycle_tracker = ProcessLifecycleTracker::default();
+  let mut alert_engine = AlertEngine::new(SystemClock);
+  let http_client = reqwest::Client::new();
+
+  // InfluxDB への送信は、送信先の応答を待ってもサンプリングが遅れないよう別のタスクで行う
+  let influx_sender = {
+    let app_handle = app_handle.clone();
+    influx_service::spawn_pusher(
+      move || app_handle.state::<config::AppState>().get_influx_settings(),
+      DiskBuffer::default(),
+    )
+  };
+
+  // OpenTelemetry のコレクターへは、記録した最新の計測値を設定した間隔で送信する
+  {
+    let app_handle = app_handle.clone();
+    otlp_service::spawn_exporter(
+      move || app_handle.state::<config::AppState>().get_otlp_settings(),
+      Arc::clone(&system),
+      Arc::clone(&metric_history),
+      utils::tauri::get_app_version(&utils::tauri::get_config()),
+    );
+  }
+
   thread::spawn(move || loop {
+    // アラートの判定に使用する今回の計測値
+    let mut metrics: HashMap<MetricKind, f32> = HashMap::new();
+
     {
       let mut sys = match system.lock() {
         Ok(s) => s,
@@ -283,6 +841,9 @@ pub fn initialize_system(
         (used_memory / total_memory * 100.0).round() as f32
       };
 
+      metrics.insert(MetricKind::CpuUsage, cpu_usage);
+      metrics.insert(MetricKind::MemoryUsage, memory_usage);
+
       {
         let mut cpu_hist = cpu_history.lock().unwrap();
         if cpu_hist.len() >= HISTORY_CAPACITY {
@@ -303,8 +864,19 @@ pub fn initialize_system(
       {
         let mut process_cpu_histories = process_cpu_histories.lock().unwrap();
         let mut process_memory_histories = process_memory_histories.lock().unwrap();
+        let mut process_sample_timestamps = process_sample_timestamps.lock().unwrap();
+
+        let sampled_at = chrono::Utc::now().timestamp_millis();
 
         for (pid, process) in sys.processes() {
+          // 計測時刻（UNIX エポックからのミリ秒）を保存
+          let timestamps = process_sample_timestamps.entry(*pid).or_default();
+
+          if timestamps.len() >= HISTORY_CAPACITY {
+            timestamps.pop_front();
+          }
+          timestamps.push_back(sampled_at);
+
           // CPU使用率の履歴を更新
           let cpu_usage = process.cpu_usage() as f32;
           let cpu_history = process_cpu_histories.entry(*pid).or_insert(VecDeque::new());
@@ -315,7 +887,7 @@ pub fn initialize_system(
           cpu_history.push_back(cpu_usage);
 
           // メモリ使用率の履歴を更新
-          let memory_usage = process.memory() as f32 / 1024.0; // KB単位からMB単位に変換
+          let memory_usage = formatter::bytes_to_mb(process.memory()); // bytes単位からMB単位に変換
           let memory_history = process_memory_histories
             .entry(*pid)
             .or_insert(VecDeque::new());
@@ -325,9 +897,316 @@ pub fn initialize_system(
           }
           memory_history.push_back(memory_usage);
         }
+
+        // 終了したプロセスの履歴は削除する
+        //
+        // PID が再利用されたときに前のプロセスの値を引き継がないよう、計測時刻と値をまとめて削除する
+        let is_alive = |pid: &Pid| sys.processes().contains_key(pid);
+        process_sample_timestamps.retain(|pid, _| is_alive(pid));
+        process_cpu_histories.retain(|pid, _| is_alive(pid));
+        process_memory_histories.retain(|pid, _| is_alive(pid));
+      }
+
+      // 各プロセスごとのディスク読み書き速度を保存
+      //
+      // コマンド側のリフレッシュで差分がリセットされないよう、累積値の差から計算する
+      let disk_rates = {
+        let mut process_disk_read_histories = process_disk_read_histories.lock().unwrap();
+        let mut process_disk_write_histories =
+          process_disk_write_histories.lock().unwrap();
+
+        let elapsed = last_sampled_at.elapsed().as_secs_f32().max(f32::EPSILON);
+        last_sampled_at = Instant::now();
+
+        let mut disk_totals = HashMap::with_capacity(sys.processes().len());
+        let mut disk_rates = HashMap::with_capacity(sys.processes().len());
+
+        for (pid, process) in sys.processes() {
+          let disk_usage = process.disk_usage();
+          let totals = (disk_usage.total_read_bytes, disk_usage.total_written_bytes);
+
+          let (read_rate, write_rate) = match last_disk_totals.get(pid) {
+            Some(&(last_read, last_written)) => (
+              totals.0.saturating_sub(last_read) as f32 / elapsed,
+              totals.1.saturating_sub(last_written) as f32 / elapsed,
+            ),
+            None => (0.0, 0.0), // 初回は差分を計算できない
+          };
+          disk_totals.insert(*pid, totals);
+          disk_rates.insert(*pid, (read_rate, write_rate));
+
+          push_history(
+            process_disk_read_histories.entry(*pid).or_default(),
+            read_rate,
+          );
+          push_history(
+            process_disk_write_histories.entry(*pid).or_default(),
+            write_rate,
+          );
+        }
+
+        // 終了したプロセスの履歴は削除する（PID が再利用されたときに引き継がないため）
+        process_disk_read_histories.retain(|pid, _| sys.processes().contains_key(pid));
+        process_disk_write_histories.retain(|pid, _| sys.processes().contains_key(pid));
+
+        last_disk_totals = disk_totals;
+
+        disk_rates
+      };
+
+      // アプリケーション（グループ）ごとの使用量を保存
+      {
+        let mut process_group_histories = process_group_histories.lock().unwrap();
+        let mut active_keys = HashSet::new();
+
+        for group_by in ProcessGroupBy::ALL {
+          let groups =
+            process_group_service::aggregate(sys.processes(), group_by, |pid| {
+              disk_rates.get(&pid).copied().unwrap_or((0.0, 0.0))
+            });
+
+          for (key, group) in groups {
+            let history = process_group_histories
+              .entry((group_by, key.clone()))
+              .or_default();
+
+            push_history(&mut history.cpu, group.cpu_usage);
+            push_history(&mut history.memory, group.memory_usage);
+            push_history(&mut history.disk_read, group.disk_read_bytes_per_sec);
+            push_history(&mut history.disk_write, group.disk_write_bytes_per_sec);
+
+            active_keys.insert((group_by, key));
+          }
+        }
+
+        // 消滅したグループの履歴は削除する
+        process_group_histories.retain(|key, _| active_keys.contains(key));
+      }
+
+      // 取得要求のあったプロセスのスレッドごとの CPU 使用率を計算
+      thread_sampler.lock().unwrap().sample();
+
+      // プロセスの起動・終了を検出し、監視ルールに一致するものを通知
+      {
+        let diff = lifecycle_tracker.update(sys.processes());
+        let rules = app_handle.state::<config::AppState>().get_process_watches();
+
+        if !rules.is_empty() && !(diff.started.is_empty() && diff.exited.is_empty()) {
+          let now = chrono::Utc::now().timestamp().max(0) as u64;
+
+          for (event, payload) in
+            process_watch_service::evaluate(&rules, &diff, &lifecycle_tracker, now)
+          {
+            if let Err(e) = app_handle.emit(event, payload) {
+              log_warn!("emit_failed", "initialize_system", Some(e.to_string()));
+            }
+          }
+        }
+      }
+
+      // メモリ使用量が単調に増加しているプロセスを検出
+      {
+        let settings = app_handle
+          .state::<config::AppState>()
+          .get_memory_leak_settings();
+
+        if settings.enabled {
+          let mut detector = memory_leak_detector.lock().unwrap();
+          let now = chrono::Utc::now().timestamp();
+
+          for suspect in detector.update(now, sys.processes(), &settings) {
+            log_info!(
+              "memory_leak_detected",
+              "initialize_system",
+              Some(format!(
+                "{} ({}): {} MB/h",
+                suspect.name, suspect.pid, suspect.growth_mb_per_hour
+              ))
+            );
+
+            if let Err(e) =
+              app_handle.emit(memory_leak_service::MEMORY_LEAK_DETECTED_EVENT, suspect)
+            {
+              log_warn!("emit_failed", "initialize_system", Some(e.to_string()));
+            }
+          }
+        }
+      }
+    }
+
+    let config_state = app_handle.state::<config::AppState>();
+    let rules = config_state.get_alert_rules();
+    let anomaly_detectors = config_state.get_anomaly_detectors();
+
+    // GPU の情報の取得中に `system` のロックを保持しないよう、ブロックの外で行う
+    if rules
+      .iter()
+      .any(|rule| rule.enabled && rule.metric.requires_gpu())
+      || anomaly_detectors
+        .iter()
+        .any(|detector| detector.enabled && detector.metric.requires_gpu())
+    {
+      sample_gpu_metrics(&mut metrics);
+    }
+
+    let sampled_at = chrono::Utc::now().timestamp_millis();
+    metric_history.lock().unwrap().push(sampled_at, &metrics);
+
+    if config_state.get_influx_settings().enabled {
+      let _ = influx_sender.send(MetricSample {
+        timestamp: sampled_at,
+        values: metrics.clone(),
+      });
+    }
+
+    // 直近の傾向から大きく外れた指標を通知
+    if !anomaly_detectors.is_empty() {
+      let detected =
+        anomaly_detector
+          .lock()
+          .unwrap()
+          .update(sampled_at, &anomaly_detectors, &metrics);
+
+      for marker in detected {
+        log_info!(
+          "anomaly_detected",
+          "initialize_system",
+          Some(format!(
+            "{:?}: {} (baseline {}, z {})",
+            marker.metric, marker.value, marker.baseline, marker.z_score
+          ))
+        );
+
+        if let Err(e) = app_handle.emit(anomaly_service::ANOMALY_DETECTED_EVENT, marker) {
+          log_warn!("emit_failed", "initialize_system", Some(e.to_string()));
+        }
+      }
+    }
+
+    // 閾値を超えた状態が続いている指標を通知
+    {
+      let commands_enabled = config_state.is_alert_commands_enabled();
+      let webhooks = config_state.get_webhooks();
+      let maintenance_windows = config_state.get_maintenance_windows();
+      let silences = config_state.get_silences(sampled_at);
+      let local_now = chrono::Local::now().naive_local();
+
+      for mut event in alert_engine.evaluate(&rules, &metrics) {
+        let mut history = alert_history.lock().unwrap();
+
+        // 発生時に通知しなかったアラートは、解消時も通知しない
+        event.silenced = silence_service::is_silenced(
+          &event.rule_id,
+          sampled_at,
+          local_now,
+          &maintenance_windows,
+          &silences,
+        ) || (event.kind == AlertEventKind::Resolved
+          && history.is_silenced(&event.rule_id, event.fired_at));
+
+        log_info!(
+          event.event_name(),
+          "initialize_system",
+          Some(format!(
+            "{}: {}{}",
+            event.rule_name,
+            event.message(),
+            if event.silenced { " (silenced)" } else { "" }
+          ))
+        );
+
+        history.record(&event);
+        drop(history);
+
+        let rule = match rules.iter().find(|rule| rule.id == event.rule_id) {
+          Some(rule) => rule,
+          None => continue,
+        };
+
+        // 履歴への記録とイベントの発生のみ行う
+        if event.silenced {
+          if let Err(e) = app_handle.emit(event.event_name(), event) {
+            log_warn!("emit_failed", "initialize_system", Some(e.to_string()));
+          }
+          continue;
+        }
+
+        if let Some(command) = &rule.command {
+          if commands_enabled {
+            alert_command_service::spawn(command, &event);
+          }
+        }
+
+        for webhook in webhooks
+          .iter()
+          .filter(|webhook| webhook.enabled && rule.webhook_ids.contains(&webhook.id))
+        {
+          webhook_service::spawn(
+            http_client.clone(),
+            webhook.clone(),
+            &event,
+            Arc::clone(&webhook_delivery_log),
+          );
+        }
+
+        if rule.notify {
+          if let Err(e) = app_handle
+            .notification()
+            .builder()
+            .title(&event.rule_name)
+            .body(event.message())
+            .show()
+          {
+            log_warn!("notify_failed", "initialize_system", Some(e.to_string()));
+          }
+        }
+
+        if let Err(e) = app_handle.emit(event.event_name(), event) {
+          log_warn!("emit_failed", "initialize_system", Some(e.to_string()));
+        }
+      }
+
+      // 履歴のファイルへの書き込みは、ロックを解放してからサンプリングごとに 1回だけ行う
+      let pending = alert_history.lock().unwrap().pending_save();
+      let saved = match pending {
+        Ok(Some(pending)) => pending.write(),
+        Ok(None) => Ok(()),
+        Err(e) => Err(e),
+      };
+      if let Err(e) = saved {
+        log_error!("record_failed", "initialize_system", Some(e));
       }
     }
 
     thread::sleep(Duration::from_secs(SYSTEM_INFO_INIT_INTERVAL));
   });
 }
+
+///
+/// ## GPU の使用率と温度を計測値に追加（Nvidia 限定）
+///
+/// - 取得できなかった値は追加しない（アラートの判定から除外される）
+/// - 複数の GPU がある場合、温度は最も高い値を使用する
+///
+fn sample_gpu_metrics(metrics: &mut HashMap<MetricKind, f32>) {
+  if let Ok(usage) =
+    tauri::async_runtime::block_on(graphic_service::get_nvidia_gpu_usage())
+  {
+    metrics.insert(MetricKind::GpuUsage, (usage * 100.0).round());
+  }
+
+  if let Ok(temperatures) =
+    tauri::async_runtime::block_on(graphic_service::get_nvidia_gpu_temperature())
+  {
+    let max = temperatures
+      .iter()
+      .map(|temperature| temperature.value as f32)
+      .fold(None, |max: Option<f32>, value| {
+        Some(max.map_or(value, |max| max.max(value)))
+      });
+
+    if let Some(max) = max {
+      metrics.insert(MetricKind::GpuTemperature, max);
+    }
+  }
+}
@@ -1,2 +1,6 @@
+pub mod alert;
+pub mod cgroup;
 pub mod config;
 pub mod hardware;
+pub mod network;
+pub mod process;
@@ -0,0 +1,29 @@
+use crate::services::network_connection_service::{
+  self, NetworkConnection, SocketProtocol,
+};
+use crate::services::procfs_service::PROC_ROOT;
+use std::path::Path;
+use tauri::command;
+
+///
+/// ## ネットワーク接続の一覧を取得（`ss` / `netstat` 相当）
+///
+/// - param protocols: `Option<Vec<SocketProtocol>>` 取得するプロトコル（省略時はすべて）
+///
+#[command]
+pub fn get_network_connections(
+  protocols: Option<Vec<SocketProtocol>>,
+) -> Result<Vec<NetworkConnection>, String> {
+  let protocols = protocols.unwrap_or_else(|| SocketProtocol::ALL.to_vec());
+  network_connection_service::get_connections(Path::new(PROC_ROOT), &protocols)
+}
+
+///
+/// ## 指定したポートで待ち受けているプロセスを取得
+///
+/// - param port: `u16` ポート番号
+///
+#[command]
+pub fn get_listening_processes(port: u16) -> Result<Vec<NetworkConnection>, String> {
+  network_connection_service::get_listeners(Path::new(PROC_ROOT), port)
+}
@@ -0,0 +1,383 @@
+use crate::commands::config;
+use crate::commands::hardware::{average_recent, AppState};
+use crate::enums::process::{ProcessGroupBy, ProcessHistoryMetric};
+use crate::services::memory_leak_service::MemoryLeakSuspect;
+use crate::services::process_group_service::{self, ProcessGroupHistory};
+use crate::services::process_service::{
+  self, PriorityClass, ProcessAction, ProcessActionResult, ProcessDetails,
+  ProcessPriority,
+};
+use crate::services::thread_service::ThreadInfo;
+use serde::Serialize;
+use std::collections::VecDeque;
+use sysinfo::Pid;
+use tauri::command;
+
+///
+/// ## プロセスを終了（SIGTERM）
+///
+/// - param pid: `u32` プロセスID
+/// - param start_time: `u64` プロセスの開始時刻（`ProcessInfo.startTime`）
+///
+#[command]
+pub fn terminate_process(
+  state: tauri::State<'_, AppState>,
+  pid: u32,
+  start_time: u64,
+) -> ProcessActionResult {
+  let mut system = state.system.lock().unwrap();
+  process_service::execute_action(&mut system, pid, start_time, ProcessAction::Terminate)
+}
+
+///
+/// ## プロセスを強制終了（SIGKILL）
+///
+/// - param pid: `u32` プロセスID
+/// - param start_time: `u64` プロセスの開始時刻（`ProcessInfo.startTime`）
+///
+#[command]
+pub fn kill_process(
+  state: tauri::State<'_, AppState>,
+  pid: u32,
+  start_time: u64,
+) -> ProcessActionResult {
+  let mut system = state.system.lock().unwrap();
+  process_service::execute_action(&mut system, pid, start_time, ProcessAction::Kill)
+}
+
+///
+/// ## プロセスを一時停止（SIGSTOP）
+///
+/// - param pid: `u32` プロセスID
+/// - param start_time: `u64` プロセスの開始時刻（`ProcessInfo.startTime`）
+///
+#[command]
+pub fn suspend_process(
+  state: tauri::State<'_, AppState>,
+  pid: u32,
+  start_time: u64,
+) -> ProcessActionResult {
+  let mut system = state.system.lock().unwrap();
+  process_service::execute_action(&mut system, pid, start_time, ProcessAction::Suspend)
+}
+
+///
+/// ## 一時停止中のプロセスを再開（SIGCONT）
+///
+/// - param pid: `u32` プロセスID
+/// - param start_time: `u64` プロセスの開始時刻（`ProcessInfo.startTime`）
+///
+#[command]
+pub fn resume_process(
+  state: tauri::State<'_, AppState>,
+  pid: u32,
+  start_time: u64,
+) -> ProcessActionResult {
+  let mut system = state.system.lock().unwrap();
+  process_service::execute_action(&mut system, pid, start_time, ProcessAction::Resume)
+}
+
+///
+/// ## プロセスの優先度を取得
+///
+/// - param pid: `u32` プロセスID
+/// - return: `ProcessPriority` 優先度クラスと nice 値（Windows では nice は `None`）
+///
+#[command]
+pub fn get_process_priority(
+  state: tauri::State<'_, AppState>,
+  pid: u32,
+) -> Result<ProcessPriority, String> {
+  let mut system = state.system.lock().unwrap();
+  process_service::get_priority(&mut system, Pid::from_u32(pid))
+}
+
+///
+/// ## プロセスの優先度を変更
+///
+/// - param pid: `u32` プロセスID
+/// - param class: `Option<PriorityClass>` 優先度クラス
+/// - param nice: `Option<i32>` nice 値（指定された場合は `class` より優先）
+/// - return: `ProcessPriority` 変更後の優先度
+///
+#[command]
+pub fn set_process_priority(
+  state: tauri::State<'_, AppState>,
+  pid: u32,
+  class: Option<PriorityClass>,
+  nice: Option<i32>,
+) -> Result<ProcessPriority, String> {
+  let mut system = state.system.lock().unwrap();
+  process_service::set_priority(&mut system, Pid::from_u32(pid), class, nice)
+}
+
+///
+/// ## プロセスの CPU アフィニティを取得
+///
+/// - param pid: `u32` プロセスID
+/// - return: `Vec<usize>` 実行可能な論理コアの番号
+///
+#[command]
+pub fn get_process_affinity(
+  state: tauri::State<'_, AppState>,
+  pid: u32,
+) -> Result<Vec<usize>, String> {
+  let mut system = state.system.lock().unwrap();
+  process_service::get_affinity(&mut system, Pid::from_u32(pid))
+}
+
+///
+/// ## プロセスの CPU アフィニティを変更
+///
+/// - param pid: `u32` プロセスID
+/// - param cpus: `Vec<usize>` 実行を許可する論理コアの番号
+/// - return: `Vec<usize>` 変更後のアフィニティ
+///
+#[command]
+pub fn set_process_affinity(
+  state: tauri::State<'_, AppState>,
+  pid: u32,
+  cpus: Vec<usize>,
+) -> Result<Vec<usize>, String> {
+  let mut system = state.system.lock().unwrap();
+  process_service::set_affinity(&mut system, Pid::from_u32(pid), cpus)
+}
+
+///
+/// ## プロセスの詳細情報を取得
+///
+/// - param pid: `u32` プロセスID
+/// - return: `ProcessDetails` コマンドライン・実行ファイル・ユーザー・スレッド数など
+///
+#[command]
+pub fn get_process_details(
+  state: tauri::State<'_, AppState>,
+  pid: u32,
+) -> Result<ProcessDetails, String> {
+  let mut system = state.system.lock().unwrap();
+  process_service::get_process_details(&mut system, pid)
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessGroup {
+  pub key: String,
+  pub name: String,
+  pub group_by: ProcessGroupBy,
+  pub instance_count: usize,
+  pub pids: Vec<u32>,
+  pub cpu_usage: f32,
+  pub memory_usage: f32,
+  pub disk_read_bytes_per_sec: f32,
+  pub disk_write_bytes_per_sec: f32,
+}
+
+///
+/// ## プロセスをアプリケーション単位でまとめて取得
+///
+/// - param group_by: `ProcessGroupBy` まとめる単位（実行ファイルのパス・プロセス名・ツリーのルート）
+/// - 使用量はグループごとの履歴から 5秒間の平均を計算する
+/// - CPU 使用率は設定（`ProcessCpuMode`）に従って換算する
+///
+#[command]
+pub fn get_process_groups(
+  state: tauri::State<'_, AppState>,
+  config_state: tauri::State<'_, config::AppState>,
+  group_by: ProcessGroupBy,
+) -> Vec<ProcessGroup> {
+  let system = state.system.lock().unwrap();
+  let histories = state.process_group_histories.lock().unwrap();
+  let cpu_scale = config_state
+    .get_process_cpu_mode()
+    .scale(system.cpus().len());
+
+  let groups =
+    process_group_service::aggregate(system.processes(), group_by, |_| (0.0, 0.0));
+
+  let mut result: Vec<ProcessGroup> = groups
+    .into_iter()
+    .map(|(key, group)| {
+      let history = histories.get(&(group_by, key.clone()));
+      let average = |select: fn(&ProcessGroupHistory) -> &VecDeque<f32>, current: f32| {
+        average_recent(history.map(select)).unwrap_or(current)
+      };
+
+      let mut pids: Vec<u32> = group.pids.iter().map(|pid| pid.as_u32()).collect();
+      pids.sort_unstable();
+
+      let cpu_usage = average(|h| &h.cpu, group.cpu_usage) * cpu_scale;
+
+      ProcessGroup {
+        cpu_usage: (cpu_usage * 10.0).round() / 10.0,
+        memory_usage: average(|h| &h.memory, group.memory_usage),
+        disk_read_bytes_per_sec: average(|h| &h.disk_read, 0.0),
+        disk_write_bytes_per_sec: average(|h| &h.disk_write, 0.0),
+        instance_count: pids.len(),
+        key,
+        name: group.name,
+        group_by,
+        pids,
+      }
+    })
+    .collect();
+
+  result.sort_by(|a, b| {
+    b.cpu_usage
+      .partial_cmp(&a.cpu_usage)
+      .unwrap_or(std::cmp::Ordering::Equal)
+  });
+  result
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessGroupHistoryResponse {
+  pub cpu: Vec<f32>,
+  pub memory: Vec<f32>,
+  pub disk_read: Vec<f32>,
+  pub disk_write: Vec<f32>,
+}
+
+///
+/// ## グループごとの使用量の履歴を取得
+///
+/// - param group_by: `ProcessGroupBy` まとめる単位
+/// - param key: `String` グループのキー（`ProcessGroup.key`）
+/// - param seconds: `usize` 取得する秒数
+///
+#[command]
+pub fn get_process_group_history(
+  state: tauri::State<'_, AppState>,
+  config_state: tauri::State<'_, config::AppState>,
+  group_by: ProcessGroupBy,
+  key: String,
+  seconds: usize,
+) -> Result<ProcessGroupHistoryResponse, String> {
+  let cpu_scale = process_cpu_scale(&state, &config_state);
+  let histories = state.process_group_histories.lock().unwrap();
+  let history = histories
+    .get(&(group_by, key.clone()))
+    .ok_or_else(|| format!("Process group not found: {}", key))?;
+
+  let recent = |values: &VecDeque<f32>| -> Vec<f32> {
+    values.iter().rev().take(seconds).cloned().collect()
+  };
+
+  Ok(ProcessGroupHistoryResponse {
+    cpu: recent(&history.cpu)
+      .into_iter()
+      .map(|usage| usage * cpu_scale)
+      .collect(),
+    memory: recent(&history.memory),
+    disk_read: recent(&history.disk_read),
+    disk_write: recent(&history.disk_write),
+  })
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessHistoryPoint {
+  pub timestamp: i64, // UNIX エポックからのミリ秒
+  pub value: f32,
+}
+
+///
+/// ## プロセスごとの使用量の履歴を取得
+///
+/// - param pid: `u32` プロセスID
+/// - param metric: `ProcessHistoryMetric` 取得する指標
+/// - param seconds: `usize` 取得する秒数
+/// - return: `Vec<ProcessHistoryPoint>` 計測時刻つきの履歴（古い順）
+/// - CPU 使用率は設定（`ProcessCpuMode`）に従って換算する
+///
+#[command]
+pub fn get_process_history(
+  state: tauri::State<'_, AppState>,
+  config_state: tauri::State<'_, config::AppState>,
+  pid: u32,
+  metric: ProcessHistoryMetric,
+  seconds: usize,
+) -> Result<Vec<ProcessHistoryPoint>, String> {
+  let pid = Pid::from_u32(pid);
+  let scale = match metric {
+    ProcessHistoryMetric::CpuUsage => process_cpu_scale(&state, &config_state),
+    _ => 1.0,
+  };
+
+  let histories = match metric {
+    ProcessHistoryMetric::CpuUsage => &state.process_cpu_histories,
+    ProcessHistoryMetric::MemoryUsage => &state.process_memory_histories,
+    ProcessHistoryMetric::DiskRead => &state.process_disk_read_histories,
+    ProcessHistoryMetric::DiskWrite => &state.process_disk_write_histories,
+  }
+  .lock()
+  .unwrap();
+  let timestamps = state.process_sample_timestamps.lock().unwrap();
+
+  let (values, timestamps) = match (histories.get(&pid), timestamps.get(&pid)) {
+    (Some(values), Some(timestamps)) => (values, timestamps),
+    _ => return Err(format!("Process history not found: {}", pid)),
+  };
+
+  // 値と計測時刻は同じタイミングで追加されるため、新しい方から対応づける
+  let mut points: Vec<ProcessHistoryPoint> = values
+    .iter()
+    .rev()
+    .zip(timestamps.iter().rev())
+    .take(seconds)
+    .map(|(value, timestamp)| ProcessHistoryPoint {
+      timestamp: *timestamp,
+      value: *value * scale,
+    })
+    .collect();
+
+  points.reverse();
+  Ok(points)
+}
+
+///
+/// ## メモリリークが疑われるプロセスを取得
+///
+/// - return: `Vec<MemoryLeakSuspect>` 回帰直線の傾きと決定係数が閾値を超えたプロセス（増加量の大きい順）
+///
+#[command]
+pub fn get_memory_leak_suspects(
+  state: tauri::State<'_, AppState>,
+) -> Vec<MemoryLeakSuspect> {
+  state.memory_leak_detector.lock().unwrap().suspects()
+}
+
+///
+/// ## プロセスのスレッド一覧を取得（Linux のみ）
+///
+/// - param pid: `u32` プロセスID
+/// - return: `Vec<ThreadInfo>` スレッドID・名前・状態・CPU 使用率・累積 CPU 時間
+/// - CPU 使用率はサンプリングの間隔で計算するため、初回の取得では `None` になる
+///
+#[command]
+pub fn get_process_threads(
+  state: tauri::State<'_, AppState>,
+  config_state: tauri::State<'_, config::AppState>,
+  pid: u32,
+) -> Result<Vec<ThreadInfo>, String> {
+  let cpu_scale = process_cpu_scale(&state, &config_state);
+  let mut threads = state.thread_sampler.lock().unwrap().threads(pid)?;
+
+  for thread in threads.iter_mut() {
+    thread.cpu_usage = thread
+      .cpu_usage
+      .map(|usage| (usage * cpu_scale * 10.0).round() / 10.0);
+  }
+
+  Ok(threads)
+}
+
+///
+/// ## 設定に従って CPU 使用率（1コア基準）に掛ける係数を取得
+///
+/// - 履歴のロックより先に `system` のロックを取得して解放する（サンプリングのループと同じ順序）
+///
+fn process_cpu_scale(state: &AppState, config_state: &config::AppState) -> f32 {
+  let cpu_count = state.system.lock().unwrap().cpus().len();
+  config_state.get_process_cpu_mode().scale(cpu_count)
+}
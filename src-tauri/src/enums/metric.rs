@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+
+///
+/// サンプリングのループで計測するシステム全体の指標
+///
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum MetricKind {
+  CpuUsage,       // %
+  MemoryUsage,    // %
+  GpuUsage,       // %
+  GpuTemperature, // ℃
+}
+
+impl MetricKind {
+  ///
+  /// ## シリアライズ時と同じ名前（CSV の列名などに使用）
+  ///
+  pub fn key(self) -> &'static str {
+    match self {
+      MetricKind::CpuUsage => "cpuUsage",
+      MetricKind::MemoryUsage => "memoryUsage",
+      MetricKind::GpuUsage => "gpuUsage",
+      MetricKind::GpuTemperature => "gpuTemperature",
+    }
+  }
+
+  ///
+  /// ## 値の単位
+  ///
+  pub fn unit(self) -> &'static str {
+    match self {
+      MetricKind::CpuUsage | MetricKind::MemoryUsage | MetricKind::GpuUsage => "%",
+      MetricKind::GpuTemperature => "°C",
+    }
+  }
+
+  ///
+  /// ## 計測に NVAPI が必要か（必要な場合のみ GPU の情報を取得する）
+  ///
+  pub fn requires_gpu(self) -> bool {
+    matches!(self, MetricKind::GpuUsage | MetricKind::GpuTemperature)
+  }
+}
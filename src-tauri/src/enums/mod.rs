@@ -1 +1,3 @@
 pub mod hardware;
+pub mod metric;
+pub mod process;
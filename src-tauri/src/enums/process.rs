@@ -0,0 +1,103 @@
+use serde::{Deserialize, Serialize};
+
+///
+/// プロセス一覧のソートキー
+///
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ProcessSortKey {
+  Pid,
+  Name,
+  User,
+  StartTime,
+  CpuUsage,
+  MemoryUsage,
+  Rss,
+  VirtualMemory,
+  SharedMemory,
+  PrivateMemory,
+  Swap,
+  Pss,
+  DiskRead,
+  DiskWrite,
+}
+
+impl ProcessSortKey {
+  ///
+  /// ## ソートに `/proc/<pid>` のメモリ内訳が必要か
+  ///
+  pub fn requires_memory_breakdown(self) -> bool {
+    matches!(
+      self,
+      ProcessSortKey::SharedMemory
+        | ProcessSortKey::PrivateMemory
+        | ProcessSortKey::Swap
+        | ProcessSortKey::Pss
+    )
+  }
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum SortDirection {
+  Ascending,
+  #[default]
+  Descending,
+}
+
+///
+/// プロセスをまとめる単位
+///
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum ProcessGroupBy {
+  ExecutablePath,
+  Name,
+  TreeRoot,
+}
+
+impl ProcessGroupBy {
+  pub const ALL: [ProcessGroupBy; 3] = [
+    ProcessGroupBy::ExecutablePath,
+    ProcessGroupBy::Name,
+    ProcessGroupBy::TreeRoot,
+  ];
+}
+
+///
+/// プロセスごとに履歴を保持している指標
+///
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ProcessHistoryMetric {
+  CpuUsage,
+  MemoryUsage,
+  DiskRead,
+  DiskWrite,
+}
+
+///
+/// プロセスの CPU 使用率の表し方
+///
+/// - `Irix`: 1コアを 100% とする（マルチスレッドのプロセスは 100% を超える）
+/// - `Solaris`: マシン全体を 100% とする（`get_cpu_usage` と同じ基準）
+///
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum ProcessCpuMode {
+  #[default]
+  Irix,
+  Solaris,
+}
+
+impl ProcessCpuMode {
+  ///
+  /// ## sysinfo の値（1コア基準）に掛ける係数
+  ///
+  pub fn scale(self, cpu_count: usize) -> f32 {
+    match self {
+      ProcessCpuMode::Irix => 1.0,
+      ProcessCpuMode::Solaris => 1.0 / cpu_count.max(1) as f32,
+    }
+  }
+}
@@ -7,8 +7,19 @@ mod enums;
 mod services;
 mod utils;
 
+use commands::alert;
+use commands::cgroup;
 use commands::config;
 use commands::hardware;
+use commands::network;
+use commands::process;
+use services::alert_history_service::AlertHistory;
+use services::anomaly_service::AnomalyDetector;
+use services::memory_leak_service::MemoryLeakDetector;
+use services::metric_history_service::MetricHistory;
+use services::prometheus_service::PrometheusExporter;
+use services::thread_service::ThreadSampler;
+use services::webhook_service::WebhookDeliveryLog;
 use tauri::Manager;
 use tauri::Wry;
 
@@ -26,6 +37,16 @@ pub fn run() {
   let gpu_history = Arc::new(Mutex::new(VecDeque::with_capacity(60)));
   let process_cpu_histories = Arc::new(Mutex::new(HashMap::new()));
   let process_memory_histories = Arc::new(Mutex::new(HashMap::new()));
+  let process_disk_read_histories = Arc::new(Mutex::new(HashMap::new()));
+  let process_disk_write_histories = Arc::new(Mutex::new(HashMap::new()));
+  let process_sample_timestamps = Arc::new(Mutex::new(HashMap::new()));
+  let memory_leak_detector = Arc::new(Mutex::new(MemoryLeakDetector::default()));
+  let thread_sampler = Arc::new(Mutex::new(ThreadSampler::default()));
+  let alert_history = Arc::new(Mutex::new(AlertHistory::load()));
+  let webhook_delivery_log = Arc::new(Mutex::new(WebhookDeliveryLog::default()));
+  let metric_history = Arc::new(Mutex::new(MetricHistory::default()));
+  let anomaly_detector = Arc::new(Mutex::new(AnomalyDetector::default()));
+  let process_group_histories = Arc::new(Mutex::new(HashMap::new()));
 
   let state = hardware::AppState {
     system: Arc::clone(&system),
@@ -35,34 +56,68 @@ pub fn run() {
     gpu_history: Arc::clone(&gpu_history),
     process_cpu_histories: Arc::clone(&process_cpu_histories),
     process_memory_histories: Arc::clone(&process_memory_histories),
+    process_disk_read_histories: Arc::clone(&process_disk_read_histories),
+    process_disk_write_histories: Arc::clone(&process_disk_write_histories),
+    process_sample_timestamps: Arc::clone(&process_sample_timestamps),
+    memory_leak_detector: Arc::clone(&memory_leak_detector),
+    thread_sampler: Arc::clone(&thread_sampler),
+    alert_history: Arc::clone(&alert_history),
+    webhook_delivery_log: Arc::clone(&webhook_delivery_log),
+    metric_history: Arc::clone(&metric_history),
+    anomaly_detector: Arc::clone(&anomaly_detector),
+    process_group_histories: Arc::clone(&process_group_histories),
   };
 
-  hardware::initialize_system(
-    system,
-    cpu_history,
-    memory_history,
-    gpu_usage,
-    gpu_history,
-    process_cpu_histories,
-    process_memory_histories,
-  );
-
   tauri::Builder::<Wry>::default()
-    .setup(|app| {
+    .setup(move |app| {
       let path_resolver = app.path();
 
       // ロガーの初期化
       utils::logger::init(path_resolver.app_log_dir().unwrap());
 
+      // `/metrics` エンドポイントの起動（設定で有効な場合のみ）
+      let prometheus_exporter = PrometheusExporter::new(Arc::clone(&system));
+      let prometheus_settings = app.state::<config::AppState>().get_prometheus_settings();
+      if let Err(e) =
+        tauri::async_runtime::block_on(prometheus_exporter.apply(&prometheus_settings))
+      {
+        log_error!("prometheus_start_failed", "run", Some(e));
+      }
+      app.manage(prometheus_exporter);
+
+      // 監視ルールの参照やイベントの通知に `AppHandle` を使うため、起動後に開始する
+      hardware::initialize_system(
+        app.handle().clone(),
+        system,
+        cpu_history,
+        memory_history,
+        gpu_usage,
+        gpu_history,
+        process_cpu_histories,
+        process_memory_histories,
+        process_disk_read_histories,
+        process_disk_write_histories,
+        process_sample_timestamps,
+        memory_leak_detector,
+        thread_sampler,
+        alert_history,
+        webhook_delivery_log,
+        metric_history,
+        anomaly_detector,
+        process_group_histories,
+      );
+
       Ok(())
     })
     .plugin(tauri_plugin_store::Builder::new().build())
     .plugin(tauri_plugin_dialog::init())
+    .plugin(tauri_plugin_notification::init())
     .plugin(tauri_plugin_window_state::Builder::default().build())
     .manage(state)
     .manage(app_state)
     .invoke_handler(tauri::generate_handler![
       hardware::get_process_list,
+      hardware::get_process_tree,
       hardware::get_cpu_usage,
       hardware::get_hardware_info,
       hardware::get_memory_usage,
@@ -72,12 +127,69 @@ pub fn run() {
       hardware::get_cpu_usage_history,
       hardware::get_memory_usage_history,
       hardware::get_gpu_usage_history,
+      hardware::get_metric_history,
+      hardware::get_anomalies,
+      hardware::export_history,
+      cgroup::get_cgroup_usage,
+      cgroup::get_cgroup_tree,
+      process::terminate_process,
+      process::kill_process,
+      process::suspend_process,
+      process::resume_process,
+      process::get_process_priority,
+      process::set_process_priority,
+      process::get_process_affinity,
+      process::set_process_affinity,
+      process::get_process_details,
+      process::get_process_groups,
+      process::get_process_group_history,
+      process::get_process_history,
+      process::get_memory_leak_suspects,
+      process::get_process_threads,
+      network::get_network_connections,
+      network::get_listening_processes,
+      alert::get_alert_history,
+      alert::acknowledge_alerts,
+      alert::clear_alert_history,
+      alert::get_webhook_deliveries,
+      alert::test_webhook,
       config::commands::get_settings,
       config::commands::set_language,
       config::commands::set_theme,
       config::commands::set_display_targets,
       config::commands::set_graph_size,
       config::commands::set_state,
+      config::commands::get_process_watches,
+      config::commands::add_process_watch,
+      config::commands::remove_process_watch,
+      config::commands::set_memory_leak_settings,
+      config::commands::set_process_cpu_mode,
+      config::commands::get_alert_rules,
+      config::commands::add_alert_rule,
+      config::commands::update_alert_rule,
+      config::commands::remove_alert_rule,
+      config::commands::set_alert_commands_enabled,
+      config::commands::get_webhooks,
+      config::commands::add_webhook,
+      config::commands::update_webhook,
+      config::commands::remove_webhook,
+      config::commands::get_anomaly_detectors,
+      config::commands::set_anomaly_detector,
+      config::commands::remove_anomaly_detector,
+      config::commands::get_maintenance_windows,
+      config::commands::add_maintenance_window,
+      config::commands::update_maintenance_window,
+      config::commands::remove_maintenance_window,
+      config::commands::get_silences,
+      config::commands::silence_alerts,
+      config::commands::remove_silence,
+      config::commands::get_prometheus_settings,
+      config::commands::set_prometheus_settings,
+      config::commands::get_influx_settings,
+      config::commands::set_influx_settings,
+      config::commands::test_influx_connection,
+      config::commands::get_otlp_settings,
+      config::commands::set_otlp_settings,
     ])
     .run(tauri::generate_context!())
     .expect("error while running tauri application");
@@ -0,0 +1,290 @@
+use crate::services::alert_service::{AlertEvent, AlertEventKind};
+use crate::{log_error, log_info, log_internal, log_warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::Read;
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+///
+/// 終了を確認する間隔
+///
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+///
+/// コマンドの終了後、標準出力・標準エラー出力を読み終えるまで待つ時間
+///
+/// - コマンドが起動したプロセスが出力を開いたままにしていると、読み込みが終わらないため
+///
+const OUTPUT_DRAIN_TIMEOUT: Duration = Duration::from_secs(2);
+
+///
+/// ログに出力する標準出力・標準エラー出力の最大文字数
+///
+const MAX_LOGGED_OUTPUT: usize = 4096;
+
+///
+/// アラートの発生時に実行するコマンド
+///
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AlertCommand {
+  pub program: String,
+  #[serde(default)]
+  pub args: Vec<String>,
+  #[serde(default)]
+  pub env: HashMap<String, String>,
+  #[serde(default = "default_timeout_secs")]
+  pub timeout_secs: u64,
+  #[serde(default)]
+  pub run_on_resolve: bool, // 解消時にも実行する
+}
+
+fn default_timeout_secs() -> u64 {
+  30
+}
+
+impl AlertCommand {
+  ///
+  /// ## コマンドの内容を検証
+  ///
+  pub fn validate(&self) -> Result<(), String> {
+    if self.program.trim().is_empty() {
+      return Err("Alert command requires a program".to_string());
+    }
+    if self.timeout_secs == 0 {
+      return Err("Timeout must be at least 1 second".to_string());
+    }
+
+    Ok(())
+  }
+}
+
+///
+/// ## アラートの内容を表す環境変数を作成
+///
+pub fn alert_env(event: &AlertEvent) -> Vec<(&'static str, String)> {
+  let kind = match event.kind {
+    AlertEventKind::Fired => "fired",
+    AlertEventKind::Resolved => "resolved",
+  };
+
+  vec![
+    ("HWMON_ALERT_KIND", kind.to_string()),
+    ("HWMON_ALERT_RULE_ID", event.rule_id.clone()),
+    ("HWMON_ALERT_RULE_NAME", event.rule_name.clone()),
+    (
+      "HWMON_ALERT_METRIC",
+      serde_json::to_value(event.metric)
+        .ok()
+        .and_then(|value| value.as_str().map(str::to_string))
+        .unwrap_or_default(),
+    ),
+    ("HWMON_ALERT_VALUE", event.value.to_string()),
+    ("HWMON_ALERT_PEAK_VALUE", event.peak_value.to_string()),
+    ("HWMON_ALERT_THRESHOLD", event.threshold.to_string()),
+    ("HWMON_ALERT_FIRED_AT", event.fired_at.to_string()),
+  ]
+}
+
+///
+/// ## アラートに応じてコマンドを実行
+///
+/// - サンプリングのループを止めないよう別スレッドで実行する
+/// - 解消時は `run_on_resolve` が有効な場合のみ実行する
+///
+pub fn spawn(command: &AlertCommand, event: &AlertEvent) {
+  if event.kind == AlertEventKind::Resolved && !command.run_on_resolve {
+    return;
+  }
+
+  let command = command.clone();
+  let env = alert_env(event);
+  let rule_name = event.rule_name.clone();
+
+  thread::spawn(move || {
+    if let Err(e) = run(&command, &env) {
+      log_error!(
+        "alert_command_failed",
+        "alert_command_service::spawn",
+        Some(format!("{}: {}", rule_name, e))
+      );
+    }
+  });
+}
+
+///
+/// ## コマンドを実行し、終了を待って出力をログに記録
+///
+/// - `timeout_secs` を過ぎても終了しない場合は強制終了する
+///
+fn run(command: &AlertCommand, env: &[(&'static str, String)]) -> Result<(), String> {
+  let mut builder = Command::new(&command.program);
+  builder
+    .args(&command.args)
+    .envs(&command.env)
+    .envs(env.iter().map(|(key, value)| (*key, value)))
+    .stdin(Stdio::null())
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped());
+  platform::configure(&mut builder);
+
+  let mut child = builder
+    .spawn()
+    .map_err(|e| format!("Failed to start {}: {}", command.program, e))?;
+
+  // 出力がパイプのバッファを超えても子プロセスが止まらないよう、並行して読み込む
+  let stdout = read_output(child.stdout.take());
+  let stderr = read_output(child.stderr.take());
+
+  let status = wait_with_timeout(&mut child, Duration::from_secs(command.timeout_secs));
+
+  let drain_deadline = Instant::now() + OUTPUT_DRAIN_TIMEOUT;
+  let drain = |output: mpsc::Receiver<String>| {
+    output
+      .recv_timeout(drain_deadline.saturating_duration_since(Instant::now()))
+      .unwrap_or_default()
+  };
+  let stdout = drain(stdout);
+  let stderr = drain(stderr);
+
+  if !stdout.is_empty() {
+    log_info!(
+      "alert_command_stdout",
+      "alert_command_service::run",
+      Some(truncate(&stdout))
+    );
+  }
+  if !stderr.is_empty() {
+    log_warn!(
+      "alert_command_stderr",
+      "alert_command_service::run",
+      Some(truncate(&stderr))
+    );
+  }
+
+  match status? {
+    Some(status) if status.success() => {
+      log_info!(
+        "alert_command_finished",
+        "alert_command_service::run",
+        Some(&command.program)
+      );
+      Ok(())
+    }
+    Some(status) => Err(format!("{} exited with {}", command.program, status)),
+    None => Err(format!(
+      "{} timed out after {} seconds",
+      command.program, command.timeout_secs
+    )),
+  }
+}
+
+///
+/// ## 終了を待つ（時間切れの場合は強制終了して `None` を返す）
+///
+/// - Linux ではコマンドが起動したプロセスもまとめて強制終了する
+///
+fn wait_with_timeout(
+  child: &mut Child,
+  timeout: Duration,
+) -> Result<Option<std::process::ExitStatus>, String> {
+  let started_at = Instant::now();
+
+  loop {
+    match child.try_wait() {
+      Ok(Some(status)) => return Ok(Some(status)),
+      Ok(None) if started_at.elapsed() >= timeout => {
+        platform::kill_tree(child);
+        let _ = child.kill();
+        let _ = child.wait();
+        return Ok(None);
+      }
+      Ok(None) => thread::sleep(POLL_INTERVAL),
+      Err(e) => return Err(format!("Failed to wait for command: {}", e)),
+    }
+  }
+}
+
+///
+/// ## 別スレッドで出力を最後まで読み込む
+///
+/// - 読み込みの完了を待つ時間を呼び出し側で制限できるよう、結果はチャネルで返す
+///
+fn read_output<R: Read + Send + 'static>(source: Option<R>) -> mpsc::Receiver<String> {
+  let (sender, receiver) = mpsc::channel();
+
+  thread::spawn(move || {
+    let mut buffer = Vec::new();
+    if let Some(mut source) = source {
+      let _ = source.read_to_end(&mut buffer);
+    }
+    let _ = sender.send(String::from_utf8_lossy(&buffer).trim().to_string());
+  });
+
+  receiver
+}
+
+fn truncate(output: &str) -> String {
+  match output.char_indices().nth(MAX_LOGGED_OUTPUT) {
+    Some((index, _)) => format!("{}...", &output[..index]),
+    None => output.to_string(),
+  }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+  use std::os::unix::process::CommandExt;
+  use std::process::{Child, Command};
+
+  ///
+  /// ## 新しいプロセスグループで実行する（時間切れのときにまとめて終了するため）
+  ///
+  pub fn configure(command: &mut Command) {
+    // fork 後の子プロセスで実行するため、非同期シグナル安全な関数のみ呼び出す
+    unsafe {
+      command.pre_exec(|| {
+        if libc::setpgid(0, 0) != 0 {
+          return Err(std::io::Error::last_os_error());
+        }
+        Ok(())
+      });
+    }
+  }
+
+  ///
+  /// ## コマンドのプロセスグループ全体を強制終了
+  ///
+  pub fn kill_tree(child: &Child) {
+    unsafe {
+      libc::kill(-(child.id() as libc::pid_t), libc::SIGKILL);
+    }
+  }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+  use std::os::windows::process::CommandExt;
+  use std::process::{Child, Command};
+  use windows::Win32::System::Threading::CREATE_NO_WINDOW;
+
+  ///
+  /// ## コンソールウィンドウを表示せずに実行する
+  ///
+  pub fn configure(command: &mut Command) {
+    command.creation_flags(CREATE_NO_WINDOW.0);
+  }
+
+  pub fn kill_tree(_child: &Child) {}
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows")))]
+mod platform {
+  use std::process::{Child, Command};
+
+  pub fn configure(_command: &mut Command) {}
+
+  pub fn kill_tree(_child: &Child) {}
+}
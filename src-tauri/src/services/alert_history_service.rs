@@ -0,0 +1,471 @@
+use crate::enums::metric::MetricKind;
+use crate::services::alert_service::{AlertComparison, AlertEvent, AlertEventKind};
+use crate::utils::file::get_app_data_dir;
+use crate::{log_error, log_internal};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+///
+/// アラートの履歴を保存するファイル名（`settings.json` と同じディレクトリ）
+///
+const ALERT_HISTORY_FILENAME: &str = "alert_history.json";
+
+///
+/// 保持する履歴の最大件数（超えた分は古い順に削除）
+///
+const MAX_ALERT_RECORDS: usize = 1000;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AlertRecord {
+  pub id: String,
+  pub rule_id: String,
+  pub rule_name: String,
+  pub metric: MetricKind,
+  pub comparison: AlertComparison,
+  pub threshold: f32,
+  pub peak_value: f32,
+  pub started_at: i64, // UNIX エポックからのミリ秒
+  pub fired_at: i64,
+  pub resolved_at: Option<i64>, // 発生中の場合は `None`
+  pub duration_millis: Option<i64>,
+  #[serde(default)]
+  pub acknowledged: bool,
+  #[serde(default)]
+  pub acknowledged_at: Option<i64>,
+  #[serde(default)]
+  pub interrupted: bool, // 解消する前にアプリが終了した
+  #[serde(default)]
+  pub silenced: bool, // メンテナンス期間中などで通知しなかった
+}
+
+impl AlertRecord {
+  ///
+  /// ## 現在発生中か
+  ///
+  pub fn is_active(&self) -> bool {
+    self.resolved_at.is_none() && !self.interrupted
+  }
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AlertHistoryFilter {
+  pub rule_id: Option<String>,
+  pub metric: Option<MetricKind>,
+  pub from: Option<i64>, // 発生時刻の範囲（UNIX エポックからのミリ秒）
+  pub to: Option<i64>,
+  pub active_only: bool,
+  pub unacknowledged_only: bool,
+  pub silenced: Option<bool>, // `true` の場合は通知しなかった履歴のみ、`false` の場合は通知した履歴のみ
+  pub limit: Option<usize>,
+}
+
+///
+/// アラートの発生と解消の履歴（再起動後も参照できるようファイルに保存する）
+///
+/// - 変更してもすぐにはファイルへ書き込まない（`pending_save` で取得した内容を、ロックを解放してから書き込む）
+///
+#[derive(Debug, Default)]
+pub struct AlertHistory {
+  path: PathBuf,
+  records: Vec<AlertRecord>,
+  revision: u64,                   // 変更のたびに増やす
+  saved_revision: Arc<Mutex<u64>>, // ファイルに書き込んだ内容の `revision`
+}
+
+///
+/// ファイルへの書き込みを待っている履歴の内容
+///
+pub struct PendingSave {
+  path: PathBuf,
+  revision: u64,
+  serialized: String,
+  saved_revision: Arc<Mutex<u64>>,
+}
+
+impl PendingSave {
+  ///
+  /// ## 履歴をファイルに書き込む
+  ///
+  /// - 書き込みの順番が前後した場合に、新しい内容を古い内容で上書きしない
+  ///
+  pub fn write(self) -> Result<(), String> {
+    let mut saved_revision = self.saved_revision.lock().unwrap();
+    if *saved_revision >= self.revision {
+      return Ok(());
+    }
+
+    if let Some(parent) = self.path.parent() {
+      fs::create_dir_all(parent)
+        .map_err(|e| format!("Failed to create directory: {}", e))?;
+    }
+
+    fs::write(&self.path, self.serialized)
+      .map_err(|e| format!("Failed to write alert history: {}", e))?;
+
+    *saved_revision = self.revision;
+    Ok(())
+  }
+}
+
+impl AlertHistory {
+  ///
+  /// ## 保存されている履歴を読み込む
+  ///
+  /// - ファイルが存在しない、または読み込めない場合は空の履歴を返す
+  ///
+  pub fn load() -> Self {
+    Self::load_from(get_app_data_dir(ALERT_HISTORY_FILENAME))
+  }
+
+  pub fn load_from(path: PathBuf) -> Self {
+    let mut records = match fs::read_to_string(&path) {
+      Ok(input) => match serde_json::from_str::<Vec<AlertRecord>>(&input) {
+        Ok(records) => records,
+        Err(e) => {
+          log_error!(
+            "deserialize_failed",
+            "AlertHistory::load",
+            Some(e.to_string())
+          );
+          vec![]
+        }
+      },
+      Err(_) => vec![], // 初回起動時はファイルが存在しない
+    };
+
+    // 前回の起動時に発生中だったアラートは、解消を記録できないため中断として扱う
+    for record in records.iter_mut() {
+      if record.resolved_at.is_none() {
+        record.interrupted = true;
+      }
+    }
+
+    Self {
+      path,
+      records,
+      ..Default::default()
+    }
+  }
+
+  ///
+  /// ## 前回の書き込み以降に変更があれば、書き込む内容を取得
+  ///
+  /// - ファイルへの書き込みに時間がかかってもサンプリングやコマンドを止めないよう、呼び出し元はロックを解放してから `PendingSave::write` を呼ぶ
+  ///
+  pub fn pending_save(&self) -> Result<Option<PendingSave>, String> {
+    if *self.saved_revision.lock().unwrap() >= self.revision {
+      return Ok(None);
+    }
+
+    let serialized = serde_json::to_string(&self.records)
+      .map_err(|e| format!("Failed to serialize alert history: {}", e))?;
+
+    Ok(Some(PendingSave {
+      path: self.path.clone(),
+      revision: self.revision,
+      serialized,
+      saved_revision: Arc::clone(&self.saved_revision),
+    }))
+  }
+
+  ///
+  /// ## アラートの発生・解消を記録
+  ///
+  /// - 解消イベントは同じルール・発生時刻の記録に反映する
+  ///
+  pub fn record(&mut self, event: &AlertEvent) {
+    match event.kind {
+      AlertEventKind::Fired => {
+        self.records.push(AlertRecord {
+          id: format!("{}-{}", event.rule_id, event.fired_at),
+          rule_id: event.rule_id.clone(),
+          rule_name: event.rule_name.clone(),
+          metric: event.metric,
+          comparison: event.comparison,
+          threshold: event.threshold,
+          peak_value: event.peak_value,
+          started_at: event.started_at,
+          fired_at: event.fired_at,
+          resolved_at: None,
+          duration_millis: None,
+          acknowledged: false,
+          acknowledged_at: None,
+          interrupted: false,
+          silenced: event.silenced,
+        });
+
+        if self.records.len() > MAX_ALERT_RECORDS {
+          let overflow = self.records.len() - MAX_ALERT_RECORDS;
+          self.records.drain(..overflow);
+        }
+      }
+      AlertEventKind::Resolved => {
+        let record = self.records.iter_mut().rev().find(|record| {
+          record.rule_id == event.rule_id && record.fired_at == event.fired_at
+        });
+
+        match record {
+          Some(record) => {
+            record.peak_value = event.peak_value;
+            record.resolved_at = event.resolved_at;
+            record.duration_millis = event.duration_millis;
+          }
+          None => return, // 削除済みの記録
+        }
+      }
+    }
+
+    self.revision += 1;
+  }
+
+  ///
+  /// ## 発生時に通知しなかったアラートか（解消時の通知も行わないため）
+  ///
+  pub fn is_silenced(&self, rule_id: &str, fired_at: i64) -> bool {
+    self
+      .records
+      .iter()
+      .rev()
+      .find(|record| record.rule_id == rule_id && record.fired_at == fired_at)
+      .map_or(false, |record| record.silenced)
+  }
+
+  ///
+  /// ## 条件に一致する履歴を取得（新しい順）
+  ///
+  pub fn list(&self, filter: &AlertHistoryFilter) -> Vec<AlertRecord> {
+    self
+      .records
+      .iter()
+      .rev()
+      .filter(|record| {
+        filter
+          .rule_id
+          .as_ref()
+          .map_or(true, |rule_id| &record.rule_id == rule_id)
+          && filter.metric.map_or(true, |metric| record.metric == metric)
+          && filter.from.map_or(true, |from| record.fired_at >= from)
+          && filter.to.map_or(true, |to| record.fired_at <= to)
+          && (!filter.active_only || record.is_active())
+          && (!filter.unacknowledged_only || !record.acknowledged)
+          && filter
+            .silenced
+            .map_or(true, |silenced| record.silenced == silenced)
+      })
+      .take(filter.limit.unwrap_or(usize::MAX))
+      .cloned()
+      .collect()
+  }
+
+  ///
+  /// ## 履歴を確認済みにする
+  ///
+  /// - return: `usize` 確認済みにした件数
+  ///
+  pub fn acknowledge(&mut self, ids: &[String], now: i64) -> usize {
+    let mut count = 0;
+
+    for record in self.records.iter_mut() {
+      if !record.acknowledged && ids.contains(&record.id) {
+        record.acknowledged = true;
+        record.acknowledged_at = Some(now);
+        count += 1;
+      }
+    }
+
+    if count > 0 {
+      self.revision += 1;
+    }
+    count
+  }
+
+  ///
+  /// ## 履歴を削除
+  ///
+  /// - param before: `Option<i64>` 指定した時刻より前に発生した履歴のみ削除（`None` の場合はすべて）
+  /// - 発生中のアラートは解消の記録先として残す
+  ///
+  pub fn clear(&mut self, before: Option<i64>) -> usize {
+    let len = self.records.len();
+
+    self.records.retain(|record| {
+      record.is_active() || before.map_or(false, |before| record.fired_at >= before)
+    });
+
+    let removed = len - self.records.len();
+    if removed > 0 {
+      self.revision += 1;
+    }
+    removed
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::sync::atomic::{AtomicUsize, Ordering};
+
+  ///
+  /// テストごとに異なる一時ディレクトリ（終了時に削除）
+  ///
+  struct TempDir(PathBuf);
+
+  impl TempDir {
+    fn new() -> Self {
+      static COUNTER: AtomicUsize = AtomicUsize::new(0);
+      Self(std::env::temp_dir().join(format!(
+        "alert-history-{}-{}",
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::SeqCst)
+      )))
+    }
+
+    fn path(&self) -> PathBuf {
+      self.0.join(ALERT_HISTORY_FILENAME)
+    }
+  }
+
+  impl Drop for TempDir {
+    fn drop(&mut self) {
+      let _ = fs::remove_dir_all(&self.0);
+    }
+  }
+
+  fn event(kind: AlertEventKind, rule_id: &str, fired_at: i64) -> AlertEvent {
+    let resolved = kind == AlertEventKind::Resolved;
+
+    AlertEvent {
+      kind,
+      rule_id: rule_id.to_string(),
+      rule_name: rule_id.to_uppercase(),
+      metric: MetricKind::CpuUsage,
+      comparison: AlertComparison::Above,
+      threshold: 90.0,
+      value: 95.0,
+      peak_value: if resolved { 99.0 } else { 95.0 },
+      started_at: fired_at - 1000,
+      fired_at,
+      resolved_at: resolved.then(|| fired_at + 5000),
+      duration_millis: resolved.then(|| 6000),
+      silenced: false,
+    }
+  }
+
+  fn save(history: &AlertHistory) {
+    if let Some(pending) = history.pending_save().unwrap() {
+      pending.write().unwrap();
+    }
+  }
+
+  #[test]
+  fn round_trip_marks_active_alerts_as_interrupted() {
+    let dir = TempDir::new();
+    let mut history = AlertHistory::load_from(dir.path());
+    assert!(history.list(&AlertHistoryFilter::default()).is_empty());
+
+    history.record(&event(AlertEventKind::Fired, "cpu", 1_000));
+    history.record(&event(AlertEventKind::Resolved, "cpu", 1_000));
+    history.record(&event(AlertEventKind::Fired, "memory", 2_000));
+    assert_eq!(history.acknowledge(&["cpu-1000".to_string()], 3_000), 1);
+    save(&history);
+
+    let loaded = AlertHistory::load_from(dir.path());
+    let records = loaded.list(&AlertHistoryFilter::default());
+
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].id, "memory-2000");
+    assert!(records[0].interrupted);
+    assert!(!records[0].is_active());
+    assert_eq!(records[1].id, "cpu-1000");
+    assert!(!records[1].interrupted);
+    assert_eq!(records[1].peak_value, 99.0);
+    assert_eq!(records[1].resolved_at, Some(6_000));
+    assert_eq!(records[1].duration_millis, Some(6_000));
+    assert_eq!(records[1].acknowledged_at, Some(3_000));
+  }
+
+  #[test]
+  fn trims_oldest_records() {
+    let dir = TempDir::new();
+    let mut history = AlertHistory::load_from(dir.path());
+
+    for index in 0..MAX_ALERT_RECORDS + 5 {
+      history.record(&event(AlertEventKind::Fired, "cpu", index as i64));
+    }
+    save(&history);
+
+    let records = AlertHistory::load_from(dir.path()).records;
+    assert_eq!(records.len(), MAX_ALERT_RECORDS);
+    assert_eq!(records[0].fired_at, 5);
+    assert_eq!(
+      records[MAX_ALERT_RECORDS - 1].fired_at,
+      (MAX_ALERT_RECORDS + 4) as i64
+    );
+  }
+
+  #[test]
+  fn clear_keeps_active_alerts() {
+    let dir = TempDir::new();
+    let mut history = AlertHistory::load_from(dir.path());
+    history.record(&event(AlertEventKind::Fired, "cpu", 1_000));
+    history.record(&event(AlertEventKind::Resolved, "cpu", 1_000));
+    history.record(&event(AlertEventKind::Fired, "cpu", 2_000));
+    history.record(&event(AlertEventKind::Resolved, "cpu", 2_000));
+    history.record(&event(AlertEventKind::Fired, "memory", 3_000));
+
+    assert_eq!(history.clear(Some(2_000)), 1);
+    assert_eq!(history.clear(None), 1);
+    save(&history);
+
+    let records = AlertHistory::load_from(dir.path()).records;
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].id, "memory-3000");
+  }
+
+  #[test]
+  fn pending_save_is_skipped_without_changes() {
+    let dir = TempDir::new();
+    let mut history = AlertHistory::load_from(dir.path());
+    assert!(history.pending_save().unwrap().is_none());
+
+    // 該当する記録がない解消イベントと確認は変更として扱わない
+    history.record(&event(AlertEventKind::Resolved, "cpu", 1_000));
+    assert_eq!(history.acknowledge(&["unknown".to_string()], 0), 0);
+    assert!(history.pending_save().unwrap().is_none());
+    assert!(!dir.path().exists());
+
+    history.record(&event(AlertEventKind::Fired, "cpu", 1_000));
+    save(&history);
+    assert!(dir.path().exists());
+    assert!(history.pending_save().unwrap().is_none());
+  }
+
+  #[test]
+  fn stale_pending_save_does_not_overwrite_newer_content() {
+    let dir = TempDir::new();
+    let mut history = AlertHistory::load_from(dir.path());
+
+    history.record(&event(AlertEventKind::Fired, "cpu", 1_000));
+    let stale = history.pending_save().unwrap().unwrap();
+    history.record(&event(AlertEventKind::Fired, "memory", 2_000));
+    let latest = history.pending_save().unwrap().unwrap();
+
+    latest.write().unwrap();
+    stale.write().unwrap();
+
+    assert_eq!(AlertHistory::load_from(dir.path()).records.len(), 2);
+  }
+
+  #[test]
+  fn corrupted_file_loads_as_empty_history() {
+    let dir = TempDir::new();
+    fs::create_dir_all(&dir.0).unwrap();
+    fs::write(dir.path(), "not json").unwrap();
+
+    assert!(AlertHistory::load_from(dir.path()).records.is_empty());
+  }
+}
@@ -0,0 +1,543 @@
+use crate::enums::metric::MetricKind;
+use crate::services::alert_command_service::AlertCommand;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+///
+/// アラートが発生したときのイベント名
+///
+pub const ALERT_FIRED_EVENT: &str = "alert_fired";
+
+///
+/// アラートが解消したときのイベント名
+///
+pub const ALERT_RESOLVED_EVENT: &str = "alert_resolved";
+
+///
+/// 閾値を超えた状態の継続時間として指定できる上限（秒）
+///
+const MAX_DURATION_SECS: u64 = 24 * 60 * 60;
+
+///
+/// 再発生を抑止する時間として指定できる上限（秒）
+///
+const MAX_COOLDOWN_SECS: u64 = 7 * 24 * 60 * 60;
+
+///
+/// 現在時刻を取得する（テストでは任意の時刻を返す実装に差し替える）
+///
+pub trait Clock {
+  ///
+  /// ## 現在時刻（UNIX エポックからのミリ秒）
+  ///
+  fn now_millis(&self) -> i64;
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+  fn now_millis(&self) -> i64 {
+    chrono::Utc::now().timestamp_millis()
+  }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum AlertComparison {
+  Above,
+  Below,
+}
+
+impl AlertComparison {
+  fn is_breached(self, value: f32, threshold: f32) -> bool {
+    match self {
+      AlertComparison::Above => value > threshold,
+      AlertComparison::Below => value < threshold,
+    }
+  }
+
+  ///
+  /// ## 解消の判定に使う閾値（ヒステリシスの分だけ閾値から離れるまで解消しない）
+  ///
+  fn resolve_threshold(self, threshold: f32, hysteresis: f32) -> f32 {
+    match self {
+      AlertComparison::Above => threshold - hysteresis,
+      AlertComparison::Below => threshold + hysteresis,
+    }
+  }
+}
+
+///
+/// アラートのルール（例: CPU 使用率が 30秒間 90% を超えたら通知）
+///
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AlertRule {
+  pub id: String,
+  pub name: String,
+  pub metric: MetricKind,
+  pub comparison: AlertComparison,
+  pub threshold: f32,
+  #[serde(default)]
+  pub duration_secs: u64, // 閾値を超えた状態がこの秒数続いたら発生させる
+  #[serde(default)]
+  pub hysteresis: f32, // 解消には閾値からこの値以上戻る必要がある
+  #[serde(default)]
+  pub cooldown_secs: u64, // 前回の発生からこの秒数が経過するまで再発生させない
+  #[serde(default = "default_true")]
+  pub enabled: bool,
+  #[serde(default = "default_true")]
+  pub notify: bool, // デスクトップ通知を表示する
+  #[serde(default)]
+  pub command: Option<AlertCommand>, // 発生時に実行するコマンド（設定で許可した場合のみ）
+  #[serde(default)]
+  pub webhook_ids: Vec<String>, // 送信先の Webhook（`WebhookTarget.id`）
+}
+
+fn default_true() -> bool {
+  true
+}
+
+///
+/// ## 秒をミリ秒に変換（設定ファイルを直接編集された場合も桁あふれしないよう飽和させる）
+///
+fn secs_to_millis(secs: u64) -> i64 {
+  i64::try_from(secs.saturating_mul(1000)).unwrap_or(i64::MAX)
+}
+
+impl AlertRule {
+  ///
+  /// ## ルールの内容を検証
+  ///
+  pub fn validate(&self) -> Result<(), String> {
+    if self.name.trim().is_empty() {
+      return Err("Alert rule requires a name".to_string());
+    }
+    if !self.threshold.is_finite() {
+      return Err("Threshold must be a finite number".to_string());
+    }
+    if !self.hysteresis.is_finite() || self.hysteresis < 0.0 {
+      return Err("Hysteresis must be zero or a positive number".to_string());
+    }
+    if self.duration_secs > MAX_DURATION_SECS {
+      return Err(format!(
+        "Duration must be at most {} seconds",
+        MAX_DURATION_SECS
+      ));
+    }
+    if self.cooldown_secs > MAX_COOLDOWN_SECS {
+      return Err(format!(
+        "Cooldown must be at most {} seconds",
+        MAX_COOLDOWN_SECS
+      ));
+    }
+    if let Some(command) = &self.command {
+      command.validate()?;
+    }
+
+    Ok(())
+  }
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum AlertEventKind {
+  Fired,
+  Resolved,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AlertEvent {
+  pub kind: AlertEventKind,
+  pub rule_id: String,
+  pub rule_name: String,
+  pub metric: MetricKind,
+  pub comparison: AlertComparison,
+  pub threshold: f32,
+  pub value: f32,      // 判定に使用した値
+  pub peak_value: f32, // 発生中に最も閾値から離れた値
+  pub started_at: i64, // 閾値を超え始めた時刻（UNIX エポックからのミリ秒）
+  pub fired_at: i64,
+  pub resolved_at: Option<i64>,
+  pub duration_millis: Option<i64>, // 閾値を超え始めてから解消するまで
+  pub silenced: bool, // メンテナンス期間中などで通知しない（履歴には記録する）
+}
+
+impl AlertEvent {
+  ///
+  /// ## イベント名
+  ///
+  pub fn event_name(&self) -> &'static str {
+    match self.kind {
+      AlertEventKind::Fired => ALERT_FIRED_EVENT,
+      AlertEventKind::Resolved => ALERT_RESOLVED_EVENT,
+    }
+  }
+
+  ///
+  /// ## デスクトップ通知の本文（タイトルにはルール名を使用する）
+  ///
+  pub fn message(&self) -> String {
+    let unit = self.metric.unit();
+
+    match self.kind {
+      AlertEventKind::Fired => format!(
+        "{:.1}{} (threshold {:.1}{})",
+        self.value, unit, self.threshold, unit
+      ),
+      AlertEventKind::Resolved => format!(
+        "Resolved at {:.1}{} (peak {:.1}{})",
+        self.value, unit, self.peak_value, unit
+      ),
+    }
+  }
+}
+
+#[derive(Debug, Clone)]
+struct ActiveAlert {
+  started_at: i64,
+  fired_at: i64,
+  peak_value: f32,
+}
+
+#[derive(Debug, Default, Clone)]
+struct RuleState {
+  breached_since: Option<i64>,
+  active: Option<ActiveAlert>,
+  last_fired_at: Option<i64>,
+}
+
+///
+/// サンプリングごとに指標をルールと照合し、アラートの発生と解消を判定する
+///
+#[derive(Debug, Default)]
+pub struct AlertEngine<C: Clock = SystemClock> {
+  clock: C,
+  states: HashMap<String, RuleState>,
+}
+
+impl<C: Clock> AlertEngine<C> {
+  pub fn new(clock: C) -> Self {
+    Self {
+      clock,
+      states: HashMap::new(),
+    }
+  }
+
+  ///
+  /// ## 指標の値をルールと照合
+  ///
+  /// - param rules: `&[AlertRule]` 評価するルール（無効なルールや削除されたルールの状態は破棄する）
+  /// - param metrics: `&HashMap<MetricKind, f32>` 今回計測した値（取得できなかった指標は含まない）
+  /// - return: `Vec<AlertEvent>` 今回発生・解消したアラート
+  ///
+  pub fn evaluate(
+    &mut self,
+    rules: &[AlertRule],
+    metrics: &HashMap<MetricKind, f32>,
+  ) -> Vec<AlertEvent> {
+    let now = self.clock.now_millis();
+    let mut events = Vec::new();
+
+    self
+      .states
+      .retain(|id, _| rules.iter().any(|rule| rule.enabled && &rule.id == id));
+
+    for rule in rules.iter().filter(|rule| rule.enabled) {
+      // 取得できなかった指標は判定せず、状態を維持する
+      let value = match metrics.get(&rule.metric) {
+        Some(value) => *value,
+        None => continue,
+      };
+
+      let state = self.states.entry(rule.id.clone()).or_default();
+
+      if let Some(active) = state.active.as_mut() {
+        let resolve_threshold = rule
+          .comparison
+          .resolve_threshold(rule.threshold, rule.hysteresis);
+
+        if rule.comparison.is_breached(value, resolve_threshold) {
+          if rule.comparison.is_breached(value, active.peak_value) {
+            active.peak_value = value;
+          }
+          continue;
+        }
+
+        let active = state.active.take().unwrap();
+        state.breached_since = None;
+
+        events.push(AlertEvent {
+          kind: AlertEventKind::Resolved,
+          rule_id: rule.id.clone(),
+          rule_name: rule.name.clone(),
+          metric: rule.metric,
+          comparison: rule.comparison,
+          threshold: rule.threshold,
+          value,
+          peak_value: active.peak_value,
+          started_at: active.started_at,
+          fired_at: active.fired_at,
+          resolved_at: Some(now),
+          duration_millis: Some(now - active.started_at),
+          silenced: false,
+        });
+        continue;
+      }
+
+      if !rule.comparison.is_breached(value, rule.threshold) {
+        state.breached_since = None;
+        continue;
+      }
+
+      let breached_since = *state.breached_since.get_or_insert(now);
+      let sustained = now - breached_since >= secs_to_millis(rule.duration_secs);
+      let cooled_down = state.last_fired_at.map_or(true, |last_fired_at| {
+        now - last_fired_at >= secs_to_millis(rule.cooldown_secs)
+      });
+
+      if sustained && cooled_down {
+        state.active = Some(ActiveAlert {
+          started_at: breached_since,
+          fired_at: now,
+          peak_value: value,
+        });
+        state.last_fired_at = Some(now);
+
+        events.push(AlertEvent {
+          kind: AlertEventKind::Fired,
+          rule_id: rule.id.clone(),
+          rule_name: rule.name.clone(),
+          metric: rule.metric,
+          comparison: rule.comparison,
+          threshold: rule.threshold,
+          value,
+          peak_value: value,
+          started_at: breached_since,
+          fired_at: now,
+          resolved_at: None,
+          duration_millis: None,
+          silenced: false,
+        });
+      }
+    }
+
+    events
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::cell::Cell;
+  use std::rc::Rc;
+
+  ///
+  /// テストから時刻を進められる `Clock`
+  ///
+  #[derive(Clone, Default)]
+  struct FakeClock(Rc<Cell<i64>>);
+
+  impl FakeClock {
+    fn set_secs(&self, secs: i64) {
+      self.0.set(secs * 1000);
+    }
+  }
+
+  impl Clock for FakeClock {
+    fn now_millis(&self) -> i64 {
+      self.0.get()
+    }
+  }
+
+  fn cpu_rule(duration_secs: u64, hysteresis: f32, cooldown_secs: u64) -> AlertRule {
+    AlertRule {
+      id: "cpu".to_string(),
+      name: "CPU".to_string(),
+      metric: MetricKind::CpuUsage,
+      comparison: AlertComparison::Above,
+      threshold: 90.0,
+      duration_secs,
+      hysteresis,
+      cooldown_secs,
+      enabled: true,
+      notify: false,
+      command: None,
+      webhook_ids: vec![],
+    }
+  }
+
+  ///
+  /// ## 指定した時刻の CPU 使用率で評価し、発生・解消したイベントの種類を返す
+  ///
+  fn evaluate_at(
+    engine: &mut AlertEngine<FakeClock>,
+    clock: &FakeClock,
+    rules: &[AlertRule],
+    secs: i64,
+    cpu_usage: f32,
+  ) -> Vec<AlertEvent> {
+    clock.set_secs(secs);
+    engine.evaluate(rules, &HashMap::from([(MetricKind::CpuUsage, cpu_usage)]))
+  }
+
+  fn kinds(events: &[AlertEvent]) -> Vec<AlertEventKind> {
+    events.iter().map(|event| event.kind).collect()
+  }
+
+  #[test]
+  fn fires_after_duration_is_sustained() {
+    let clock = FakeClock::default();
+    let mut engine = AlertEngine::new(clock.clone());
+    let rules = [cpu_rule(30, 0.0, 0)];
+
+    assert!(evaluate_at(&mut engine, &clock, &rules, 0, 95.0).is_empty());
+    assert!(evaluate_at(&mut engine, &clock, &rules, 29, 95.0).is_empty());
+
+    let events = evaluate_at(&mut engine, &clock, &rules, 30, 96.0);
+    assert_eq!(kinds(&events), vec![AlertEventKind::Fired]);
+    assert_eq!(events[0].started_at, 0);
+    assert_eq!(events[0].fired_at, 30_000);
+    assert_eq!(events[0].value, 96.0);
+
+    // 発生中は再度発生させない
+    assert!(evaluate_at(&mut engine, &clock, &rules, 31, 97.0).is_empty());
+  }
+
+  #[test]
+  fn recovery_before_duration_resets_timer() {
+    let clock = FakeClock::default();
+    let mut engine = AlertEngine::new(clock.clone());
+    let rules = [cpu_rule(30, 0.0, 0)];
+
+    assert!(evaluate_at(&mut engine, &clock, &rules, 0, 95.0).is_empty());
+    assert!(evaluate_at(&mut engine, &clock, &rules, 20, 50.0).is_empty());
+    assert!(evaluate_at(&mut engine, &clock, &rules, 40, 95.0).is_empty());
+    assert!(evaluate_at(&mut engine, &clock, &rules, 60, 95.0).is_empty());
+
+    let events = evaluate_at(&mut engine, &clock, &rules, 70, 95.0);
+    assert_eq!(kinds(&events), vec![AlertEventKind::Fired]);
+    assert_eq!(events[0].started_at, 40_000);
+  }
+
+  #[test]
+  fn resolves_only_past_hysteresis() {
+    let clock = FakeClock::default();
+    let mut engine = AlertEngine::new(clock.clone());
+    let rules = [cpu_rule(0, 5.0, 0)];
+
+    let events = evaluate_at(&mut engine, &clock, &rules, 0, 92.0);
+    assert_eq!(kinds(&events), vec![AlertEventKind::Fired]);
+
+    // 閾値を下回っても、ヒステリシスの範囲内では解消しない
+    assert!(evaluate_at(&mut engine, &clock, &rules, 1, 88.0).is_empty());
+    assert!(evaluate_at(&mut engine, &clock, &rules, 2, 97.0).is_empty());
+    assert!(evaluate_at(&mut engine, &clock, &rules, 3, 85.5).is_empty());
+
+    let events = evaluate_at(&mut engine, &clock, &rules, 4, 85.0);
+    assert_eq!(kinds(&events), vec![AlertEventKind::Resolved]);
+    assert_eq!(events[0].value, 85.0);
+    assert_eq!(events[0].peak_value, 97.0);
+    assert_eq!(events[0].fired_at, 0);
+    assert_eq!(events[0].resolved_at, Some(4_000));
+    assert_eq!(events[0].duration_millis, Some(4_000));
+  }
+
+  #[test]
+  fn cooldown_suppresses_refiring() {
+    let clock = FakeClock::default();
+    let mut engine = AlertEngine::new(clock.clone());
+    let rules = [cpu_rule(0, 0.0, 60)];
+
+    assert_eq!(
+      kinds(&evaluate_at(&mut engine, &clock, &rules, 0, 95.0)),
+      vec![AlertEventKind::Fired]
+    );
+    assert_eq!(
+      kinds(&evaluate_at(&mut engine, &clock, &rules, 1, 50.0)),
+      vec![AlertEventKind::Resolved]
+    );
+    assert!(evaluate_at(&mut engine, &clock, &rules, 2, 95.0).is_empty());
+    assert!(evaluate_at(&mut engine, &clock, &rules, 59, 95.0).is_empty());
+
+    let events = evaluate_at(&mut engine, &clock, &rules, 60, 95.0);
+    assert_eq!(kinds(&events), vec![AlertEventKind::Fired]);
+    // 閾値を超え始めたのはクールダウン中の時刻
+    assert_eq!(events[0].started_at, 2_000);
+  }
+
+  #[test]
+  fn below_comparison_uses_inverse_hysteresis() {
+    let clock = FakeClock::default();
+    let mut engine = AlertEngine::new(clock.clone());
+    let rules = [AlertRule {
+      comparison: AlertComparison::Below,
+      threshold: 10.0,
+      ..cpu_rule(0, 2.0, 0)
+    }];
+
+    assert_eq!(
+      kinds(&evaluate_at(&mut engine, &clock, &rules, 0, 5.0)),
+      vec![AlertEventKind::Fired]
+    );
+    assert!(evaluate_at(&mut engine, &clock, &rules, 1, 11.0).is_empty());
+    assert!(evaluate_at(&mut engine, &clock, &rules, 2, 3.0).is_empty());
+
+    let events = evaluate_at(&mut engine, &clock, &rules, 3, 12.5);
+    assert_eq!(kinds(&events), vec![AlertEventKind::Resolved]);
+    assert_eq!(events[0].peak_value, 3.0);
+  }
+
+  #[test]
+  fn missing_metric_keeps_state_and_disabled_rule_resets_it() {
+    let clock = FakeClock::default();
+    let mut engine = AlertEngine::new(clock.clone());
+    let rules = [cpu_rule(30, 0.0, 0)];
+
+    assert!(evaluate_at(&mut engine, &clock, &rules, 0, 95.0).is_empty());
+
+    // 取得できなかった指標は判定しない
+    clock.set_secs(10);
+    assert!(engine.evaluate(&rules, &HashMap::new()).is_empty());
+
+    assert_eq!(
+      kinds(&evaluate_at(&mut engine, &clock, &rules, 30, 95.0)),
+      vec![AlertEventKind::Fired]
+    );
+
+    // 無効にしたルールの状態は破棄され、再度有効にすると初めから判定する
+    let disabled = [AlertRule {
+      enabled: false,
+      ..cpu_rule(30, 0.0, 0)
+    }];
+    assert!(evaluate_at(&mut engine, &clock, &disabled, 31, 95.0).is_empty());
+    assert!(evaluate_at(&mut engine, &clock, &rules, 32, 95.0).is_empty());
+    assert_eq!(
+      kinds(&evaluate_at(&mut engine, &clock, &rules, 62, 95.0)),
+      vec![AlertEventKind::Fired]
+    );
+  }
+
+  #[test]
+  fn huge_durations_do_not_overflow() {
+    let clock = FakeClock::default();
+    let mut engine = AlertEngine::new(clock.clone());
+    let rules = [cpu_rule(u64::MAX, 0.0, u64::MAX)];
+
+    assert!(evaluate_at(&mut engine, &clock, &rules, 0, 95.0).is_empty());
+    assert!(evaluate_at(&mut engine, &clock, &rules, 3600, 95.0).is_empty());
+  }
+
+  #[test]
+  fn validate_bounds_duration_and_cooldown() {
+    assert!(cpu_rule(MAX_DURATION_SECS, 0.0, MAX_COOLDOWN_SECS)
+      .validate()
+      .is_ok());
+    assert!(cpu_rule(MAX_DURATION_SECS + 1, 0.0, 0).validate().is_err());
+    assert!(cpu_rule(0, 0.0, MAX_COOLDOWN_SECS + 1).validate().is_err());
+    assert!(cpu_rule(0, -1.0, 0).validate().is_err());
+    assert!(cpu_rule(0, f32::NAN, 0).validate().is_err());
+  }
+}
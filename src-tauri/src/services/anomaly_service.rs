@@ -0,0 +1,413 @@
+use crate::enums::metric::MetricKind;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::collections::VecDeque;
+
+///
+/// 指標が直近の傾向から外れたときのイベント名
+///
+pub const ANOMALY_DETECTED_EVENT: &str = "anomaly_detected";
+
+///
+/// メモリ上に保持するマーカーの件数
+///
+const MAX_ANOMALY_MARKERS: usize = 1000;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum AnomalyMethod {
+  #[default]
+  Ewma, // 指数加重移動平均と分散
+  ZScore, // 直近 `window_size` 件の平均と標準偏差
+}
+
+///
+/// 指標ごとの異常検知の設定
+///
+/// - 既定値は周期性を考慮しない（直近の値のみを基準にする）
+///
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AnomalyDetectorSettings {
+  pub metric: MetricKind,
+  #[serde(default = "default_true")]
+  pub enabled: bool,
+  #[serde(default)]
+  pub method: AnomalyMethod,
+  #[serde(default = "default_ewma_alpha")]
+  pub ewma_alpha: f32, // 新しい値の重み（`Ewma` のみ）
+  #[serde(default = "default_window_size")]
+  pub window_size: usize, // 基準とするサンプル数（`ZScore` のみ）
+  #[serde(default = "default_threshold_sigma")]
+  pub threshold_sigma: f32, // 基準から標準偏差の何倍離れたら異常とするか
+  #[serde(default = "default_min_deviation")]
+  pub min_deviation: f32, // 変動の少ない指標で誤検知しないよう、基準からの最小の差（指標の単位）
+  #[serde(default = "default_warmup_samples")]
+  pub warmup_samples: usize, // 基準が安定するまで判定しないサンプル数
+}
+
+fn default_true() -> bool {
+  true
+}
+
+fn default_ewma_alpha() -> f32 {
+  0.1
+}
+
+fn default_window_size() -> usize {
+  120
+}
+
+fn default_threshold_sigma() -> f32 {
+  3.0
+}
+
+fn default_min_deviation() -> f32 {
+  5.0
+}
+
+fn default_warmup_samples() -> usize {
+  30
+}
+
+impl AnomalyDetectorSettings {
+  ///
+  /// ## 設定の内容を検証
+  ///
+  pub fn validate(&self) -> Result<(), String> {
+    if !(self.ewma_alpha > 0.0 && self.ewma_alpha <= 1.0) {
+      return Err("EWMA alpha must be greater than 0 and at most 1".to_string());
+    }
+    if self.window_size < 2 {
+      return Err("Window size must be at least 2 samples".to_string());
+    }
+    if !self.threshold_sigma.is_finite() || self.threshold_sigma <= 0.0 {
+      return Err("Threshold must be a positive number".to_string());
+    }
+    if !self.min_deviation.is_finite() || self.min_deviation < 0.0 {
+      return Err("Minimum deviation must be zero or a positive number".to_string());
+    }
+
+    Ok(())
+  }
+}
+
+///
+/// 基準から外れたサンプル
+///
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AnomalyMarker {
+  pub metric: MetricKind,
+  pub method: AnomalyMethod,
+  pub timestamp: i64, // UNIX エポックからのミリ秒
+  pub value: f32,
+  pub baseline: f32, // 判定時点の基準値（平均）
+  pub std_dev: f32,
+  pub z_score: f32, // 正の場合は基準より高い
+}
+
+#[derive(Debug, Default)]
+struct DetectorState {
+  method: AnomalyMethod,
+  count: usize,
+  mean: f32,
+  variance: f32,
+  window: VecDeque<f32>,
+  anomalous: bool, // 直前のサンプルが異常だった
+}
+
+impl DetectorState {
+  ///
+  /// ## 今回の値を判定する前の基準（平均・標準偏差）
+  ///
+  fn baseline(&self) -> (f32, f32) {
+    match self.method {
+      AnomalyMethod::Ewma => (self.mean, self.variance.sqrt()),
+      AnomalyMethod::ZScore => {
+        let len = self.window.len().max(1) as f32;
+        let mean = self.window.iter().sum::<f32>() / len;
+        let variance = self
+          .window
+          .iter()
+          .map(|value| (value - mean).powi(2))
+          .sum::<f32>()
+          / len;
+        (mean, variance.sqrt())
+      }
+    }
+  }
+
+  fn update(&mut self, value: f32, settings: &AnomalyDetectorSettings) {
+    match self.method {
+      AnomalyMethod::Ewma => {
+        if self.count == 0 {
+          self.mean = value;
+          self.variance = 0.0;
+        } else {
+          let alpha = settings.ewma_alpha;
+          let diff = value - self.mean;
+          self.mean += alpha * diff;
+          self.variance = (1.0 - alpha) * (self.variance + alpha * diff * diff);
+        }
+      }
+      AnomalyMethod::ZScore => {
+        while self.window.len() >= settings.window_size {
+          self.window.pop_front();
+        }
+        self.window.push_back(value);
+      }
+    }
+
+    self.count += 1;
+  }
+}
+
+///
+/// サンプリングごとに指標を直近の基準と比較し、大きく外れた値を検出する
+///
+#[derive(Debug, Default)]
+pub struct AnomalyDetector {
+  states: HashMap<MetricKind, DetectorState>,
+  markers: VecDeque<AnomalyMarker>,
+}
+
+impl AnomalyDetector {
+  ///
+  /// ## 指標の値を判定して基準を更新
+  ///
+  /// - param timestamp: `i64` 計測時刻（UNIX エポックからのミリ秒）
+  /// - param settings: `&[AnomalyDetectorSettings]` 指標ごとの設定（無効・削除された指標の基準は破棄する）
+  /// - param metrics: `&HashMap<MetricKind, f32>` 今回計測した値
+  /// - return: `Vec<AnomalyMarker>` 正常な状態から異常になった指標（異常が続いている間は最初の 1件のみ）
+  ///
+  pub fn update(
+    &mut self,
+    timestamp: i64,
+    settings: &[AnomalyDetectorSettings],
+    metrics: &HashMap<MetricKind, f32>,
+  ) -> Vec<AnomalyMarker> {
+    let mut detected = Vec::new();
+
+    // 方式を変更した場合も基準を作り直す
+    self.states.retain(|metric, state| {
+      settings.iter().any(|detector| {
+        detector.enabled && detector.metric == *metric && detector.method == state.method
+      })
+    });
+
+    for detector in settings.iter().filter(|detector| detector.enabled) {
+      let value = match metrics.get(&detector.metric) {
+        Some(value) => *value,
+        None => continue,
+      };
+
+      let state = self
+        .states
+        .entry(detector.metric)
+        .or_insert_with(|| DetectorState {
+          method: detector.method,
+          ..Default::default()
+        });
+
+      let marker = if state.count >= detector.warmup_samples {
+        let (baseline, std_dev) = state.baseline();
+        let deviation = value - baseline;
+        let z_score = if std_dev > f32::EPSILON {
+          deviation / std_dev
+        } else if deviation.abs() > f32::EPSILON {
+          f32::INFINITY.copysign(deviation)
+        } else {
+          0.0
+        };
+
+        (z_score.abs() >= detector.threshold_sigma
+          && deviation.abs() >= detector.min_deviation)
+          .then(|| AnomalyMarker {
+            metric: detector.metric,
+            method: detector.method,
+            timestamp,
+            value,
+            baseline,
+            std_dev,
+            z_score: z_score.clamp(f32::MIN, f32::MAX), // JSON で表せるよう有限の値にする
+          })
+      } else {
+        None
+      };
+
+      state.update(value, detector);
+
+      let was_anomalous = state.anomalous;
+      state.anomalous = marker.is_some();
+
+      if let Some(marker) = marker {
+        if self.markers.len() >= MAX_ANOMALY_MARKERS {
+          self.markers.pop_front();
+        }
+        self.markers.push_back(marker.clone());
+
+        if !was_anomalous {
+          detected.push(marker);
+        }
+      }
+    }
+
+    detected
+  }
+
+  ///
+  /// ## 記録した異常のマーカーを取得（古い順）
+  ///
+  /// - param metric: `Option<MetricKind>` 指定した指標のみ取得
+  /// - param from: `Option<i64>` 指定した時刻以降のみ取得（UNIX エポックからのミリ秒）
+  ///
+  pub fn markers(
+    &self,
+    metric: Option<MetricKind>,
+    from: Option<i64>,
+  ) -> Vec<AnomalyMarker> {
+    self
+      .markers
+      .iter()
+      .filter(|marker| {
+        metric.map_or(true, |metric| marker.metric == metric)
+          && from.map_or(true, |from| marker.timestamp >= from)
+      })
+      .cloned()
+      .collect()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn detector_settings(method: AnomalyMethod) -> AnomalyDetectorSettings {
+    AnomalyDetectorSettings {
+      metric: MetricKind::CpuUsage,
+      enabled: true,
+      method,
+      ewma_alpha: 0.1,
+      window_size: 4,
+      threshold_sigma: 3.0,
+      min_deviation: 0.0,
+      warmup_samples: 4,
+    }
+  }
+
+  ///
+  /// 1秒間隔の計測時刻で順に値を判定し、新たに検出した件数を返す
+  ///
+  struct Feeder {
+    detector: AnomalyDetector,
+    settings: Vec<AnomalyDetectorSettings>,
+    timestamp: i64,
+  }
+
+  impl Feeder {
+    fn new(settings: AnomalyDetectorSettings) -> Self {
+      Self {
+        detector: AnomalyDetector::default(),
+        settings: vec![settings],
+        timestamp: 0,
+      }
+    }
+
+    fn feed(&mut self, value: f32) -> Vec<AnomalyMarker> {
+      self.timestamp += 1000;
+      let metrics = HashMap::from([(MetricKind::CpuUsage, value)]);
+      self
+        .detector
+        .update(self.timestamp, &self.settings, &metrics)
+    }
+
+    fn feed_all(&mut self, values: &[f32]) -> usize {
+      values.iter().map(|value| self.feed(*value).len()).sum()
+    }
+
+    fn baseline(&self) -> (f32, f32) {
+      self.detector.states[&MetricKind::CpuUsage].baseline()
+    }
+  }
+
+  #[test]
+  fn does_not_detect_during_warmup() {
+    let mut feeder = Feeder::new(detector_settings(AnomalyMethod::ZScore));
+
+    assert_eq!(feeder.feed_all(&[10.0, 12.0, 10.0, 90.0]), 0);
+    assert!(feeder.detector.markers(None, None).is_empty());
+  }
+
+  #[test]
+  fn z_score_detects_threshold_crossing_once_per_episode() {
+    let mut feeder = Feeder::new(detector_settings(AnomalyMethod::ZScore));
+    feeder.feed_all(&[10.0, 12.0, 10.0, 12.0]);
+
+    // 平均 11、標準偏差 1 に対して z = 4
+    let detected = feeder.feed(15.0);
+    assert_eq!(detected.len(), 1);
+    assert_eq!(detected[0].timestamp, 5000);
+    assert_eq!(detected[0].baseline, 11.0);
+    assert_eq!(detected[0].std_dev, 1.0);
+    assert_eq!(detected[0].z_score, 4.0);
+
+    // 異常が続いている間は新たに通知しないが、マーカーは記録する
+    assert!(feeder.feed(40.0).is_empty());
+    assert_eq!(feeder.detector.markers(None, None).len(), 2);
+
+    // 正常に戻った後の異常は再び通知する
+    assert!(feeder.feed(12.0).is_empty());
+    assert_eq!(feeder.feed(100.0).len(), 1);
+    assert_eq!(feeder.detector.markers(None, Some(8000)).len(), 1);
+  }
+
+  #[test]
+  fn ewma_detects_values_beyond_threshold_sigma() {
+    let mut feeder = Feeder::new(detector_settings(AnomalyMethod::Ewma));
+    for index in 0..40 {
+      feeder.feed(if index % 2 == 0 { 49.0 } else { 51.0 });
+    }
+
+    let (mean, std_dev) = feeder.baseline();
+    assert!((mean - 50.0).abs() < 1.0);
+    assert!(std_dev > 0.5 && std_dev < 2.0);
+
+    assert!(feeder.feed(mean + 2.0 * std_dev).is_empty());
+
+    let (mean, std_dev) = feeder.baseline();
+    let detected = feeder.feed(mean - 4.0 * std_dev);
+    assert_eq!(detected.len(), 1);
+    assert!(detected[0].z_score < -3.0);
+    assert_eq!(detected[0].method, AnomalyMethod::Ewma);
+  }
+
+  #[test]
+  fn min_deviation_suppresses_small_changes_of_flat_metrics() {
+    let mut settings = detector_settings(AnomalyMethod::Ewma);
+    settings.min_deviation = 5.0;
+    let mut feeder = Feeder::new(settings);
+    feeder.feed_all(&[50.0; 10]);
+
+    // 標準偏差が 0 のため z は無限大になるが、差が小さいため異常としない
+    assert!(feeder.feed(51.0).is_empty());
+
+    let mut feeder = Feeder::new(feeder.settings[0].clone());
+    feeder.feed_all(&[50.0; 10]);
+    let detected = feeder.feed(60.0);
+    assert_eq!(detected.len(), 1);
+    assert_eq!(detected[0].z_score, f32::MAX);
+  }
+
+  #[test]
+  fn changing_method_resets_baseline() {
+    let mut feeder = Feeder::new(detector_settings(AnomalyMethod::Ewma));
+    feeder.feed_all(&[50.0; 10]);
+
+    feeder.settings[0].method = AnomalyMethod::ZScore;
+
+    // 基準を作り直すため、再びウォームアップする
+    assert!(feeder.feed(90.0).is_empty());
+    assert_eq!(feeder.detector.states[&MetricKind::CpuUsage].count, 1);
+  }
+}
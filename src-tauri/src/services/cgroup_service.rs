@@ -0,0 +1,561 @@
+use crate::{log_debug, log_error, log_internal, log_warn};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+///
+/// cgroup v2 のマウントポイント（既定値）
+///
+pub const DEFAULT_CGROUP_ROOT: &str = "/sys/fs/cgroup";
+
+///
+/// 自プロセスが所属する cgroup を記述したファイル
+///
+const PROC_SELF_CGROUP: &str = "/proc/self/cgroup";
+
+#[derive(Debug, Default, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CgroupCpuStat {
+  pub usage_usec: u64,
+  pub user_usec: u64,
+  pub system_usec: u64,
+  pub nr_periods: Option<u64>,
+  pub nr_throttled: Option<u64>,
+  pub throttled_usec: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CgroupMemoryStat {
+  pub current: u64,
+  pub max: Option<u64>, // `max` の場合は None（無制限）
+}
+
+#[derive(Debug, Default, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CgroupIoStat {
+  pub device: String,
+  pub rbytes: u64,
+  pub wbytes: u64,
+  pub rios: u64,
+  pub wios: u64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CgroupStats {
+  pub path: String,
+  pub cpu: Option<CgroupCpuStat>,
+  pub memory: Option<CgroupMemoryStat>,
+  pub io: Vec<CgroupIoStat>,
+  pub pids_current: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CgroupNode {
+  pub stats: CgroupStats,
+  pub children: Vec<CgroupNode>,
+}
+
+///
+/// ## 指定したディレクトリが cgroup v2 の階層かどうかを判定
+///
+/// - v2 の階層には `cgroup.controllers` が必ず存在する
+///
+pub fn is_cgroup_v2(root: &Path) -> bool {
+  root.join("cgroup.controllers").exists()
+}
+
+///
+/// ## 自プロセスが所属する cgroup のパスを取得
+///
+/// - return: `String` cgroup ルートからの相対パス（例: `/user.slice/app.scope`）
+///
+pub fn detect_current_cgroup() -> Result<String, String> {
+  let content = fs::read_to_string(PROC_SELF_CGROUP).map_err(|e| {
+    log_warn!(
+      "read_proc_self_cgroup_failed",
+      "detect_current_cgroup",
+      Some(e.to_string())
+    );
+    format!("Failed to read {}: {}", PROC_SELF_CGROUP, e)
+  })?;
+
+  parse_proc_cgroup(&content).ok_or_else(|| "cgroup v2 hierarchy not found".to_string())
+}
+
+///
+/// ## `/proc/<pid>/cgroup` の内容から v2 のパスを取り出す
+///
+/// - v2 の行は `0::<path>` の形式
+///
+pub fn parse_proc_cgroup(content: &str) -> Option<String> {
+  content
+    .lines()
+    .find_map(|line| line.strip_prefix("0::"))
+    .map(|path| path.trim().to_string())
+}
+
+///
+/// ## 自プロセスが所属する cgroup の使用状況を取得
+///
+/// - param root: `&Path` cgroup v2 のマウントポイント
+///
+pub fn get_current_cgroup_stats(root: &Path) -> Result<CgroupStats, String> {
+  let cgroup_path = detect_current_cgroup()?;
+  get_cgroup_stats(root, &cgroup_path)
+}
+
+///
+/// ## 指定した cgroup の使用状況を取得
+///
+/// - param root: `&Path` cgroup v2 のマウントポイント
+/// - param cgroup_path: `&str` ルートからの相対パス
+///
+pub fn get_cgroup_stats(root: &Path, cgroup_path: &str) -> Result<CgroupStats, String> {
+  if !is_cgroup_v2(root) {
+    log_warn!(
+      "not_cgroup_v2",
+      "get_cgroup_stats",
+      Some(root.display().to_string())
+    );
+    return Err(format!("{} is not a cgroup v2 hierarchy", root.display()));
+  }
+
+  let dir = resolve_cgroup_dir(root, cgroup_path);
+
+  if !dir.is_dir() {
+    return Err(format!("cgroup not found: {}", cgroup_path));
+  }
+
+  Ok(read_cgroup_dir(&dir, cgroup_path))
+}
+
+///
+/// ## cgroup 階層全体の使用状況をツリー形式で取得
+///
+/// - param root: `&Path` cgroup v2 のマウントポイント
+///
+pub fn get_cgroup_tree(root: &Path) -> Result<CgroupNode, String> {
+  if !is_cgroup_v2(root) {
+    return Err(format!("{} is not a cgroup v2 hierarchy", root.display()));
+  }
+
+  log_debug!("start", "get_cgroup_tree", None::<&str>);
+
+  let tree = build_cgroup_node(root, "/");
+
+  log_debug!("end", "get_cgroup_tree", None::<&str>);
+
+  Ok(tree)
+}
+
+fn build_cgroup_node(dir: &Path, cgroup_path: &str) -> CgroupNode {
+  let stats = read_cgroup_dir(dir, cgroup_path);
+
+  let mut children = Vec::new();
+
+  match fs::read_dir(dir) {
+    Ok(entries) => {
+      for entry in entries.flatten() {
+        let child_dir = entry.path();
+
+        // 子 cgroup はディレクトリとして表現される（シンボリックリンクは辿らない）
+        match entry.file_type() {
+          Ok(file_type) if file_type.is_dir() => {}
+          _ => continue,
+        }
+
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let child_path = if cgroup_path == "/" {
+          format!("/{}", name)
+        } else {
+          format!("{}/{}", cgroup_path, name)
+        };
+
+        children.push(build_cgroup_node(&child_dir, &child_path));
+      }
+    }
+    Err(e) => {
+      log_error!(
+        "read_dir_failed",
+        "build_cgroup_node",
+        Some(format!("{}: {}", dir.display(), e))
+      );
+    }
+  }
+
+  children.sort_by(|a, b| a.stats.path.cmp(&b.stats.path));
+
+  CgroupNode { stats, children }
+}
+
+fn resolve_cgroup_dir(root: &Path, cgroup_path: &str) -> PathBuf {
+  let relative = cgroup_path.trim_start_matches('/');
+
+  if relative.is_empty() {
+    root.to_path_buf()
+  } else {
+    root.join(relative)
+  }
+}
+
+///
+/// ## cgroup ディレクトリ内の各ファイルを読み込む
+///
+/// - ルート cgroup など、コントローラが有効でないファイルは `None` として扱う
+///
+fn read_cgroup_dir(dir: &Path, cgroup_path: &str) -> CgroupStats {
+  let cpu = read_optional(dir, "cpu.stat").map(|s| parse_cpu_stat(&s));
+  let memory = read_optional(dir, "memory.current").and_then(|current| {
+    let current = current.trim().parse::<u64>().ok()?;
+    let max = read_optional(dir, "memory.max").and_then(|s| parse_max_value(&s));
+    Some(CgroupMemoryStat { current, max })
+  });
+  let io = read_optional(dir, "io.stat")
+    .map(|s| parse_io_stat(&s))
+    .unwrap_or_default();
+  let pids_current =
+    read_optional(dir, "pids.current").and_then(|s| s.trim().parse::<u64>().ok());
+
+  CgroupStats {
+    path: cgroup_path.to_string(),
+    cpu,
+    memory,
+    io,
+    pids_current,
+  }
+}
+
+fn read_optional(dir: &Path, file_name: &str) -> Option<String> {
+  fs::read_to_string(dir.join(file_name)).ok()
+}
+
+///
+/// ## `cpu.stat` を解析
+///
+/// - 各行は `<key> <value>` の形式
+///
+pub fn parse_cpu_stat(content: &str) -> CgroupCpuStat {
+  let mut stat = CgroupCpuStat::default();
+
+  for line in content.lines() {
+    let mut parts = line.split_whitespace();
+    let (key, value) = match (parts.next(), parts.next()) {
+      (Some(key), Some(value)) => (key, value),
+      _ => continue,
+    };
+    let value = match value.parse::<u64>() {
+      Ok(value) => value,
+      Err(_) => continue,
+    };
+
+    match key {
+      "usage_usec" => stat.usage_usec = value,
+      "user_usec" => stat.user_usec = value,
+      "system_usec" => stat.system_usec = value,
+      "nr_periods" => stat.nr_periods = Some(value),
+      "nr_throttled" => stat.nr_throttled = Some(value),
+      "throttled_usec" => stat.throttled_usec = Some(value),
+      _ => {}
+    }
+  }
+
+  stat
+}
+
+///
+/// ## `io.stat` を解析
+///
+/// - 各行は `<major>:<minor> rbytes=<n> wbytes=<n> rios=<n> wios=<n> ...` の形式
+///
+pub fn parse_io_stat(content: &str) -> Vec<CgroupIoStat> {
+  content
+    .lines()
+    .filter_map(|line| {
+      let mut parts = line.split_whitespace();
+      let device = parts.next()?.to_string();
+      let mut stat = CgroupIoStat {
+        device,
+        ..Default::default()
+      };
+
+      for part in parts {
+        let (key, value) = match part.split_once('=') {
+          Some((key, value)) => (key, value),
+          None => continue,
+        };
+        let value = match value.parse::<u64>() {
+          Ok(value) => value,
+          Err(_) => continue,
+        };
+
+        match key {
+          "rbytes" => stat.rbytes = value,
+          "wbytes" => stat.wbytes = value,
+          "rios" => stat.rios = value,
+          "wios" => stat.wios = value,
+          _ => {}
+        }
+      }
+
+      Some(stat)
+    })
+    .collect()
+}
+
+///
+/// ## `memory.max` などの上限値を解析
+///
+/// - `max` は上限なしを表すため `None` を返す
+///
+pub fn parse_max_value(content: &str) -> Option<u64> {
+  match content.trim() {
+    "max" => None,
+    value => value.parse::<u64>().ok(),
+  }
+}
+
+///
+/// ## cgroup ルートを解決
+///
+/// - 指定がなければ `DEFAULT_CGROUP_ROOT` を使用する
+/// - 任意のディレクトリを読み取れないよう、指定された場合は `DEFAULT_CGROUP_ROOT` 配下のみ許可する
+///
+pub fn resolve_root(root: Option<String>) -> Result<PathBuf, String> {
+  match root {
+    Some(root) => resolve_root_under(Path::new(&root), Path::new(DEFAULT_CGROUP_ROOT)),
+    None => Ok(PathBuf::from(DEFAULT_CGROUP_ROOT)),
+  }
+}
+
+///
+/// ## 指定されたルートを正規化し、`base` 配下か確認
+///
+/// - シンボリックリンクや `..` で `base` の外を指定できないよう、両方を正規化してから比較する
+///
+fn resolve_root_under(root: &Path, base: &Path) -> Result<PathBuf, String> {
+  let base = base
+    .canonicalize()
+    .map_err(|e| format!("Failed to resolve {}: {}", base.display(), e))?;
+  let root = root
+    .canonicalize()
+    .map_err(|e| format!("Invalid cgroup root {}: {}", root.display(), e))?;
+
+  if !root.starts_with(&base) {
+    return Err(format!(
+      "cgroup root must be under {}: {}",
+      base.display(),
+      root.display()
+    ));
+  }
+
+  Ok(root)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::sync::atomic::{AtomicUsize, Ordering};
+
+  ///
+  /// テストごとに一時ディレクトリへ cgroup v2 の階層を作成し、終了時に削除する
+  ///
+  struct CgroupFixture {
+    root: PathBuf,
+  }
+
+  impl CgroupFixture {
+    fn new() -> Self {
+      static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+      let root = std::env::temp_dir().join(format!(
+        "cgroup_fixture_{}_{}",
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::SeqCst)
+      ));
+      let fixture = Self { root };
+
+      fixture.write("/", "cgroup.controllers", "cpu io memory pids\n");
+      fixture.write(
+        "/",
+        "cpu.stat",
+        "usage_usec 1000\nuser_usec 600\nsystem_usec 400\n",
+      );
+
+      fixture.write(
+        "/user.slice",
+        "cpu.stat",
+        "usage_usec 500\nuser_usec 300\nsystem_usec 200\n\
+         nr_periods 10\nnr_throttled 2\nthrottled_usec 50\n",
+      );
+      fixture.write("/user.slice", "memory.current", "4096\n");
+      fixture.write("/user.slice", "memory.max", "max\n");
+      fixture.write(
+        "/user.slice",
+        "io.stat",
+        "8:0 rbytes=1024 wbytes=2048 rios=1 wios=2 dbytes=0 dios=0\n",
+      );
+      fixture.write("/user.slice", "pids.current", "3\n");
+
+      fixture.write("/user.slice/app.scope", "memory.current", "1024\n");
+      fixture.write("/user.slice/app.scope", "memory.max", "2048\n");
+
+      fixture.write("/system.slice", "pids.current", "7\n");
+
+      fixture
+    }
+
+    fn write(&self, cgroup_path: &str, file_name: &str, content: &str) {
+      let dir = resolve_cgroup_dir(&self.root, cgroup_path);
+      fs::create_dir_all(&dir).unwrap();
+      fs::write(dir.join(file_name), content).unwrap();
+    }
+  }
+
+  impl Drop for CgroupFixture {
+    fn drop(&mut self) {
+      let _ = fs::remove_dir_all(&self.root);
+    }
+  }
+
+  #[test]
+  fn reads_stats_of_child_cgroup() {
+    let fixture = CgroupFixture::new();
+
+    let stats = get_cgroup_stats(&fixture.root, "/user.slice").unwrap();
+
+    assert_eq!(stats.path, "/user.slice");
+
+    let cpu = stats.cpu.unwrap();
+    assert_eq!(cpu.usage_usec, 500);
+    assert_eq!(cpu.user_usec, 300);
+    assert_eq!(cpu.system_usec, 200);
+    assert_eq!(cpu.nr_periods, Some(10));
+    assert_eq!(cpu.nr_throttled, Some(2));
+    assert_eq!(cpu.throttled_usec, Some(50));
+
+    let memory = stats.memory.unwrap();
+    assert_eq!(memory.current, 4096);
+    assert_eq!(memory.max, None);
+
+    assert_eq!(stats.io.len(), 1);
+    assert_eq!(stats.io[0].device, "8:0");
+    assert_eq!(stats.io[0].rbytes, 1024);
+    assert_eq!(stats.io[0].wbytes, 2048);
+    assert_eq!(stats.io[0].rios, 1);
+    assert_eq!(stats.io[0].wios, 2);
+
+    assert_eq!(stats.pids_current, Some(3));
+  }
+
+  #[test]
+  fn missing_controller_files_are_none() {
+    let fixture = CgroupFixture::new();
+
+    let stats = get_cgroup_stats(&fixture.root, "/").unwrap();
+
+    assert_eq!(stats.cpu.unwrap().usage_usec, 1000);
+    assert!(stats.memory.is_none());
+    assert!(stats.io.is_empty());
+    assert!(stats.pids_current.is_none());
+  }
+
+  #[test]
+  fn reads_memory_limit() {
+    let fixture = CgroupFixture::new();
+
+    let stats = get_cgroup_stats(&fixture.root, "user.slice/app.scope").unwrap();
+
+    let memory = stats.memory.unwrap();
+    assert_eq!(memory.current, 1024);
+    assert_eq!(memory.max, Some(2048));
+  }
+
+  #[test]
+  fn builds_sorted_tree() {
+    let fixture = CgroupFixture::new();
+
+    let tree = get_cgroup_tree(&fixture.root).unwrap();
+
+    assert_eq!(tree.stats.path, "/");
+    let paths: Vec<&str> = tree
+      .children
+      .iter()
+      .map(|child| child.stats.path.as_str())
+      .collect();
+    assert_eq!(paths, vec!["/system.slice", "/user.slice"]);
+
+    let user_slice = &tree.children[1];
+    assert_eq!(user_slice.children.len(), 1);
+    assert_eq!(user_slice.children[0].stats.path, "/user.slice/app.scope");
+    assert!(user_slice.children[0].children.is_empty());
+  }
+
+  #[test]
+  fn rejects_non_v2_root_and_unknown_cgroup() {
+    let fixture = CgroupFixture::new();
+
+    fs::remove_file(fixture.root.join("cgroup.controllers")).unwrap();
+    assert!(get_cgroup_stats(&fixture.root, "/user.slice").is_err());
+    assert!(get_cgroup_tree(&fixture.root).is_err());
+
+    fixture.write("/", "cgroup.controllers", "cpu\n");
+    assert!(get_cgroup_stats(&fixture.root, "/missing.slice").is_err());
+  }
+
+  #[test]
+  fn parses_proc_cgroup() {
+    assert_eq!(
+      parse_proc_cgroup("0::/user.slice/app.scope\n"),
+      Some("/user.slice/app.scope".to_string())
+    );
+    assert_eq!(
+      parse_proc_cgroup("12:cpu,cpuacct:/legacy\n0::/unified\n"),
+      Some("/unified".to_string())
+    );
+    assert_eq!(parse_proc_cgroup("12:cpu,cpuacct:/legacy\n"), None);
+  }
+
+  #[test]
+  fn parses_max_value() {
+    assert_eq!(parse_max_value("max\n"), None);
+    assert_eq!(parse_max_value("1048576\n"), Some(1048576));
+    assert_eq!(parse_max_value("invalid"), None);
+  }
+
+  #[test]
+  fn resolves_default_root() {
+    assert_eq!(resolve_root(None), Ok(PathBuf::from(DEFAULT_CGROUP_ROOT)));
+    assert!(resolve_root(Some("/tmp".to_string())).is_err());
+    assert!(resolve_root(Some("/sys/fs/cgroup/../../../etc".to_string())).is_err());
+  }
+
+  #[test]
+  fn restricts_root_to_base_directory() {
+    let fixture = CgroupFixture::new();
+    let base = &fixture.root;
+
+    assert_eq!(
+      resolve_root_under(&base.join("user.slice"), base),
+      Ok(base.canonicalize().unwrap().join("user.slice"))
+    );
+    assert_eq!(
+      resolve_root_under(base, base),
+      Ok(base.canonicalize().unwrap())
+    );
+    assert!(resolve_root_under(&base.join("user.slice/../.."), base).is_err());
+    assert!(resolve_root_under(&base.join("missing"), base).is_err());
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn rejects_symlink_out_of_base_directory() {
+    let fixture = CgroupFixture::new();
+    let link = fixture.root.join("escape");
+    std::os::unix::fs::symlink(std::env::temp_dir(), &link).unwrap();
+
+    assert!(resolve_root_under(&link, &fixture.root).is_err());
+  }
+}
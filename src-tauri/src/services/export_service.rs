@@ -0,0 +1,208 @@
+use crate::enums::metric::MetricKind;
+use crate::services::metric_history_service::MetricSample;
+use chrono::{DateTime, SecondsFormat};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ExportFormat {
+  Csv,
+  Ndjson, // 1行に 1つの JSON オブジェクト
+}
+
+impl ExportFormat {
+  pub fn extension(self) -> &'static str {
+    match self {
+      ExportFormat::Csv => "csv",
+      ExportFormat::Ndjson => "ndjson",
+    }
+  }
+}
+
+///
+/// ## 計測時刻を ISO-8601 形式（UTC、ミリ秒まで）に変換
+///
+/// - 日時として表せない値の場合は `None` を返す
+///
+pub fn format_timestamp(timestamp: i64) -> Option<String> {
+  DateTime::from_timestamp_millis(timestamp)
+    .map(|datetime| datetime.to_rfc3339_opts(SecondsFormat::Millis, true))
+}
+
+///
+/// ## 履歴をファイルの内容に変換
+///
+/// - param samples: `&[MetricSample]` 出力するサンプル（古い順）
+/// - param metrics: `&[MetricKind]` 出力する指標（列の順番）
+/// - 計測できなかった値は CSV では空欄、NDJSON では `null` にする
+/// - 計測時刻が日時として表せないサンプルは出力しない
+///
+pub fn render(
+  samples: &[MetricSample],
+  metrics: &[MetricKind],
+  format: ExportFormat,
+) -> String {
+  match format {
+    ExportFormat::Csv => render_csv(samples, metrics),
+    ExportFormat::Ndjson => render_ndjson(samples, metrics),
+  }
+}
+
+fn render_csv(samples: &[MetricSample], metrics: &[MetricKind]) -> String {
+  let mut output = String::from("timestamp");
+  for metric in metrics {
+    output.push(',');
+    output.push_str(metric.key());
+  }
+  output.push('\n');
+
+  for sample in samples {
+    let timestamp = match format_timestamp(sample.timestamp) {
+      Some(timestamp) => timestamp,
+      None => continue,
+    };
+
+    output.push_str(&timestamp);
+    for metric in metrics {
+      output.push(',');
+      if let Some(value) = sample.values.get(metric).filter(|value| value.is_finite()) {
+        output.push_str(&value.to_string());
+      }
+    }
+    output.push('\n');
+  }
+
+  output
+}
+
+///
+/// ## NDJSON に変換（列の順番を保つため、オブジェクトは手動で組み立てる）
+///
+fn render_ndjson(samples: &[MetricSample], metrics: &[MetricKind]) -> String {
+  let mut output = String::new();
+
+  for sample in samples {
+    let timestamp = match format_timestamp(sample.timestamp) {
+      Some(timestamp) => timestamp,
+      None => continue,
+    };
+
+    output.push_str("{\"timestamp\":");
+    output.push_str(&Value::String(timestamp).to_string());
+
+    for metric in metrics {
+      let value = sample
+        .values
+        .get(metric)
+        .map_or(Value::Null, |value| Value::from(*value));
+      output.push_str(&format!(",\"{}\":{}", metric.key(), value));
+    }
+
+    output.push_str("}\n");
+  }
+
+  output
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::collections::HashMap;
+
+  const METRICS: [MetricKind; 3] = [
+    MetricKind::CpuUsage,
+    MetricKind::MemoryUsage,
+    MetricKind::GpuTemperature,
+  ];
+
+  fn sample(timestamp: i64, values: &[(MetricKind, f32)]) -> MetricSample {
+    MetricSample {
+      timestamp,
+      values: values.iter().copied().collect::<HashMap<_, _>>(),
+    }
+  }
+
+  fn samples() -> Vec<MetricSample> {
+    vec![
+      sample(
+        1_700_000_000_123,
+        &[
+          (MetricKind::GpuTemperature, 65.0),
+          (MetricKind::CpuUsage, 12.5),
+          (MetricKind::MemoryUsage, 40.0),
+        ],
+      ),
+      // GPU の温度を計測できなかったサンプル
+      sample(
+        1_700_000_001_000,
+        &[
+          (MetricKind::CpuUsage, 20.0),
+          (MetricKind::MemoryUsage, 41.25),
+        ],
+      ),
+    ]
+  }
+
+  #[test]
+  fn format_timestamp_uses_utc_with_milliseconds() {
+    assert_eq!(
+      format_timestamp(1_700_000_000_123),
+      Some("2023-11-14T22:13:20.123Z".to_string())
+    );
+    assert_eq!(
+      format_timestamp(0),
+      Some("1970-01-01T00:00:00.000Z".to_string())
+    );
+    assert_eq!(format_timestamp(i64::MAX), None);
+  }
+
+  #[test]
+  fn render_csv_keeps_column_order_and_leaves_missing_values_empty() {
+    let output = render(&samples(), &METRICS, ExportFormat::Csv);
+
+    assert_eq!(
+      output,
+      "timestamp,cpuUsage,memoryUsage,gpuTemperature\n\
+       2023-11-14T22:13:20.123Z,12.5,40,65\n\
+       2023-11-14T22:13:21.000Z,20,41.25,\n"
+    );
+  }
+
+  #[test]
+  fn render_ndjson_keeps_key_order_and_writes_null_for_missing_values() {
+    let output = render(&samples(), &METRICS, ExportFormat::Ndjson);
+
+    assert_eq!(
+      output,
+      "{\"timestamp\":\"2023-11-14T22:13:20.123Z\",\"cpuUsage\":12.5,\"memoryUsage\":40.0,\"gpuTemperature\":65.0}\n\
+       {\"timestamp\":\"2023-11-14T22:13:21.000Z\",\"cpuUsage\":20.0,\"memoryUsage\":41.25,\"gpuTemperature\":null}\n"
+    );
+  }
+
+  #[test]
+  fn render_writes_only_header_for_empty_range() {
+    assert_eq!(
+      render(&[], &METRICS, ExportFormat::Csv),
+      "timestamp,cpuUsage,memoryUsage,gpuTemperature\n"
+    );
+    assert_eq!(render(&[], &METRICS, ExportFormat::Ndjson), "");
+  }
+
+  #[test]
+  fn render_skips_samples_with_out_of_range_timestamps() {
+    let samples = [
+      sample(i64::MAX, &[(MetricKind::CpuUsage, 1.0)]),
+      sample(0, &[(MetricKind::CpuUsage, f32::NAN)]),
+    ];
+
+    assert_eq!(
+      render(&samples, &[MetricKind::CpuUsage], ExportFormat::Csv),
+      "timestamp,cpuUsage\n1970-01-01T00:00:00.000Z,\n"
+    );
+    assert_eq!(
+      render(&samples, &[MetricKind::CpuUsage], ExportFormat::Ndjson),
+      "{\"timestamp\":\"1970-01-01T00:00:00.000Z\",\"cpuUsage\":null}\n"
+    );
+  }
+}
@@ -75,8 +75,8 @@ pub async fn get_nvidia_gpu_usage() -> Result<f32, nvapi::Status> {
 #[derive(Debug, Clone, serde::Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct NameValue {
-  name: String,
-  value: f64, // 摂氏温度
+  pub name: String,
+  pub value: f64, // 摂氏温度
 }
 
 ///
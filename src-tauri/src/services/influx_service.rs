@@ -0,0 +1,839 @@
+use crate::services::metric_history_service::MetricSample;
+use crate::utils::file::get_app_data_dir;
+use crate::{log_debug, log_error, log_internal, log_warn};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::Duration;
+use sysinfo::System;
+use tokio::net::UdpSocket;
+use tokio::sync::mpsc::{self, UnboundedSender};
+use tokio::time::Instant;
+
+///
+/// 送信できなかった行を保存するファイル名
+///
+const BUFFER_FILENAME: &str = "influx_buffer.lp";
+
+///
+/// UDP の 1パケットに含める最大バイト数（IP フラグメントを避ける）
+///
+const MAX_UDP_PAYLOAD: usize = 1400;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum InfluxTransport {
+  #[default]
+  Http,
+  Udp,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct InfluxSettings {
+  pub enabled: bool,
+  pub transport: InfluxTransport,
+  pub url: String, // HTTP の書き込み先（例: `http://localhost:8086/api/v2/write?org=org&bucket=bucket`）
+  pub token: Option<String>, // `Authorization: Token ...` として送信する
+  pub udp_address: String, // UDP の送信先（例: `127.0.0.1:8089`）
+  pub measurement: String,
+  pub tags: BTreeMap<String, String>, // `host` を指定しない場合はホスト名を使用する
+  pub batch_size: usize,
+  pub flush_interval_secs: u64,
+  pub timeout_secs: u64,
+  pub max_buffered_lines: usize, // 送信できない間にファイルへ保存する最大行数（超えた分は古い順に削除）
+}
+
+impl Default for InfluxSettings {
+  fn default() -> Self {
+    Self {
+      enabled: false,
+      transport: InfluxTransport::Http,
+      url: "http://localhost:8086/api/v2/write?org=&bucket=hardware_monitor".to_string(),
+      token: None,
+      udp_address: "127.0.0.1:8089".to_string(),
+      measurement: "hardware_monitor".to_string(),
+      tags: BTreeMap::new(),
+      batch_size: 100,
+      flush_interval_secs: 10,
+      timeout_secs: 10,
+      max_buffered_lines: 100_000,
+    }
+  }
+}
+
+impl InfluxSettings {
+  ///
+  /// ## 設定の内容を検証
+  ///
+  pub fn validate(&self) -> Result<(), String> {
+    match self.transport {
+      InfluxTransport::Http => {
+        let url = reqwest::Url::parse(&self.url)
+          .map_err(|e| format!("Invalid InfluxDB URL: {}", e))?;
+        if !matches!(url.scheme(), "http" | "https") {
+          return Err(format!("Unsupported URL scheme: {}", url.scheme()));
+        }
+      }
+      InfluxTransport::Udp => {
+        if self.udp_address.trim().is_empty() {
+          return Err("UDP address is required".to_string());
+        }
+      }
+    }
+
+    if self.measurement.trim().is_empty() {
+      return Err("Measurement name is required".to_string());
+    }
+    if self.tags.keys().any(|key| key.trim().is_empty()) {
+      return Err("Tag keys must not be empty".to_string());
+    }
+    if self.batch_size == 0 {
+      return Err("Batch size must be at least 1".to_string());
+    }
+    if self.flush_interval_secs == 0 || self.timeout_secs == 0 {
+      return Err("Intervals must be at least 1 second".to_string());
+    }
+
+    Ok(())
+  }
+
+  ///
+  /// ## 送信するタグ（`host` が未指定の場合はホスト名を追加）
+  ///
+  pub fn resolved_tags(&self) -> BTreeMap<String, String> {
+    let mut tags = self.tags.clone();
+    if !tags.contains_key("host") {
+      if let Some(host_name) = System::host_name() {
+        tags.insert("host".to_string(), host_name);
+      }
+    }
+    tags.retain(|_, value| !value.is_empty()); // 空の値のタグは line protocol で表せない
+    tags
+  }
+}
+
+///
+/// ## line protocol のメジャーメント名をエスケープ
+///
+/// - 改行は line protocol で表せないため、`\n`（バックスラッシュと `n`）に置き換える
+///
+fn escape_measurement(value: &str) -> String {
+  value
+    .replace('\\', "\\\\")
+    .replace('\n', "\\n")
+    .replace(',', "\\,")
+    .replace(' ', "\\ ")
+}
+
+///
+/// ## line protocol のタグキー・タグの値・フィールドキーをエスケープ
+///
+/// - 改行は line protocol で表せないため、`\n`（バックスラッシュと `n`）に置き換える
+///
+fn escape_key(value: &str) -> String {
+  value
+    .replace('\\', "\\\\")
+    .replace('\n', "\\n")
+    .replace(',', "\\,")
+    .replace('=', "\\=")
+    .replace(' ', "\\ ")
+}
+
+///
+/// ## 計測値を InfluxDB の line protocol の 1行に変換
+///
+/// - タイムスタンプはナノ秒（InfluxDB の既定の精度）
+/// - 計測できた値がない場合は `None`
+///
+pub fn to_line(
+  sample: &MetricSample,
+  measurement: &str,
+  tags: &BTreeMap<String, String>,
+) -> Option<String> {
+  // 出力を安定させるため、フィールドは名前順に並べる
+  let fields: BTreeMap<&str, f32> = sample
+    .values
+    .iter()
+    .filter(|(_, value)| value.is_finite())
+    .map(|(metric, value)| (metric.key(), *value))
+    .collect();
+
+  if fields.is_empty() {
+    return None;
+  }
+
+  let mut line = escape_measurement(measurement);
+  for (key, value) in tags {
+    line.push_str(&format!(",{}={}", escape_key(key), escape_key(value)));
+  }
+
+  let fields = fields
+    .iter()
+    .map(|(key, value)| format!("{}={}", escape_key(key), value))
+    .collect::<Vec<_>>()
+    .join(",");
+
+  Some(format!(
+    "{} {} {}",
+    line,
+    fields,
+    sample.timestamp * 1_000_000
+  ))
+}
+
+///
+/// 送信に失敗した理由
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct SendError {
+  pub message: String,
+  pub retryable: bool, // 接続エラー・5xx・429 の場合のみ後で再送する
+}
+
+impl SendError {
+  fn retryable(message: String) -> Self {
+    Self {
+      message,
+      retryable: true,
+    }
+  }
+}
+
+///
+/// ## 行をまとめて送信
+///
+pub async fn send_batch(
+  client: &reqwest::Client,
+  settings: &InfluxSettings,
+  lines: &[String],
+) -> Result<(), SendError> {
+  match settings.transport {
+    InfluxTransport::Http => {
+      let mut request = client
+        .post(&settings.url)
+        .timeout(Duration::from_secs(settings.timeout_secs))
+        .header(reqwest::header::CONTENT_TYPE, "text/plain; charset=utf-8")
+        .body(lines.join("\n"));
+      if let Some(token) = settings.token.as_deref().filter(|token| !token.is_empty()) {
+        request =
+          request.header(reqwest::header::AUTHORIZATION, format!("Token {}", token));
+      }
+
+      let response = request
+        .send()
+        .await
+        .map_err(|e| SendError::retryable(e.to_string()))?;
+      if response.status().is_success() {
+        Ok(())
+      } else {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        Err(SendError {
+          message: format!("HTTP {}: {}", status, body.trim()),
+          retryable: status.is_server_error() || status.as_u16() == 429,
+        })
+      }
+    }
+    InfluxTransport::Udp => {
+      let socket = UdpSocket::bind("0.0.0.0:0")
+        .await
+        .map_err(|e| SendError::retryable(e.to_string()))?;
+      socket
+        .connect(settings.udp_address.trim())
+        .await
+        .map_err(|e| {
+          SendError::retryable(format!(
+            "Failed to resolve {}: {}",
+            settings.udp_address, e
+          ))
+        })?;
+
+      for packet in pack_lines(lines, MAX_UDP_PAYLOAD) {
+        socket
+          .send(packet.as_bytes())
+          .await
+          .map_err(|e| SendError::retryable(e.to_string()))?;
+      }
+      Ok(())
+    }
+  }
+}
+
+///
+/// ## 行を改行区切りでつなげ、1パケットの最大サイズごとに分割
+///
+/// - 1行で最大サイズを超える場合はその行のみのパケットにする
+///
+fn pack_lines(lines: &[String], max_bytes: usize) -> Vec<String> {
+  let mut packets = Vec::new();
+  let mut packet = String::new();
+
+  for line in lines {
+    if !packet.is_empty() && packet.len() + 1 + line.len() > max_bytes {
+      packets.push(std::mem::take(&mut packet));
+    }
+    if !packet.is_empty() {
+      packet.push('\n');
+    }
+    packet.push_str(line);
+  }
+  if !packet.is_empty() {
+    packets.push(packet);
+  }
+
+  packets
+}
+
+///
+/// 送信できなかった行を保存し、送信できるようになったら再送する
+///
+#[derive(Debug)]
+pub struct DiskBuffer {
+  path: PathBuf,
+}
+
+impl Default for DiskBuffer {
+  fn default() -> Self {
+    Self::new(get_app_data_dir(BUFFER_FILENAME))
+  }
+}
+
+impl DiskBuffer {
+  pub fn new(path: PathBuf) -> Self {
+    Self { path }
+  }
+
+  pub fn load(&self) -> Vec<String> {
+    fs::read_to_string(&self.path)
+      .map(|content| {
+        content
+          .lines()
+          .filter(|line| !line.is_empty())
+          .map(str::to_string)
+          .collect()
+      })
+      .unwrap_or_default()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    !self.path.exists()
+  }
+
+  ///
+  /// ## 保存している行を置き換える（空の場合はファイルを削除）
+  ///
+  pub fn replace(&self, lines: &[String]) -> Result<(), String> {
+    if lines.is_empty() {
+      return match fs::remove_file(&self.path) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(format!("Failed to remove buffer: {}", e)),
+      };
+    }
+
+    if let Some(parent) = self.path.parent() {
+      fs::create_dir_all(parent)
+        .map_err(|e| format!("Failed to create directory: {}", e))?;
+    }
+
+    let mut file = fs::File::create(&self.path)
+      .map_err(|e| format!("Failed to write buffer: {}", e))?;
+    for line in lines {
+      writeln!(file, "{}", line).map_err(|e| format!("Failed to write buffer: {}", e))?;
+    }
+    Ok(())
+  }
+}
+
+///
+/// ## 送信待ちの行を送信
+///
+/// - 順番を保つため、ファイルに保存している行から送信する
+/// - 送信できなかった行はファイルに保存する（`max_buffered_lines` を超えた分は古い順に削除）
+/// - 再送しても成功しないエラー（400・401 など）の場合は、後続の行を止めないようそのまとまりを破棄する
+///
+pub async fn flush(
+  client: &reqwest::Client,
+  settings: &InfluxSettings,
+  buffer: &DiskBuffer,
+  pending: Vec<String>,
+) {
+  let mut lines = buffer.load();
+  let buffered_len = lines.len();
+  lines.extend(pending);
+
+  let mut sent = 0;
+  for batch in lines.chunks(settings.batch_size) {
+    match send_batch(client, settings, batch).await {
+      Ok(()) => {}
+      Err(e) if e.retryable => {
+        log_warn!(
+          "send_failed",
+          "influx_service::flush",
+          Some(format!(
+            "{} ({} lines pending)",
+            e.message,
+            lines.len() - sent
+          ))
+        );
+        break;
+      }
+      Err(e) => {
+        log_error!(
+          "batch_dropped",
+          "influx_service::flush",
+          Some(format!("{} ({} lines dropped)", e.message, batch.len()))
+        );
+      }
+    }
+    sent += batch.len();
+  }
+
+  if sent == lines.len() && buffered_len == 0 {
+    log_debug!("flushed", "influx_service::flush", None::<&str>);
+    return;
+  }
+
+  let mut remaining = lines.split_off(sent);
+  if remaining.len() > settings.max_buffered_lines {
+    let overflow = remaining.len() - settings.max_buffered_lines;
+    log_warn!(
+      "buffer_overflow",
+      "influx_service::flush",
+      Some(format!("dropped {} lines", overflow))
+    );
+    remaining.drain(..overflow);
+  }
+
+  if let Err(e) = buffer.replace(&remaining) {
+    log_error!("buffer_failed", "influx_service::flush", Some(e));
+  }
+}
+
+///
+/// ## 計測値を受け取り、まとめて送信するタスクを開始
+///
+/// - param get_settings: 送信のたびに最新の設定を取得する（設定の変更を再起動なしで反映する）
+/// - return: 計測値の送信先（無効な間に送られた計測値は破棄する）
+///
+pub fn spawn_pusher<F>(
+  get_settings: F,
+  buffer: DiskBuffer,
+) -> UnboundedSender<MetricSample>
+where
+  F: Fn() -> InfluxSettings + Send + 'static,
+{
+  let (sender, mut receiver) = mpsc::unbounded_channel::<MetricSample>();
+
+  tauri::async_runtime::spawn(async move {
+    let client = reqwest::Client::new();
+    let mut pending: Vec<String> = Vec::new();
+    let mut next_flush = Instant::now();
+
+    loop {
+      let settings = get_settings();
+      let flush_interval = Duration::from_secs(settings.flush_interval_secs.max(1));
+
+      // 送信間隔が経過するまで計測値を待つ
+      if let Ok(sample) = tokio::time::timeout_at(next_flush, receiver.recv()).await {
+        let sample = match sample {
+          Some(sample) => sample,
+          None => break, // サンプリングのループが終了した
+        };
+        if !settings.enabled {
+          pending.clear();
+          continue;
+        }
+
+        if let Some(line) =
+          to_line(&sample, &settings.measurement, &settings.resolved_tags())
+        {
+          pending.push(line);
+        }
+        if pending.len() < settings.batch_size {
+          continue;
+        }
+      }
+
+      next_flush = Instant::now() + flush_interval;
+      if settings.enabled && !(pending.is_empty() && buffer.is_empty()) {
+        flush(&client, &settings, &buffer, std::mem::take(&mut pending)).await;
+      }
+    }
+  });
+
+  sender
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::enums::metric::MetricKind;
+  use std::collections::HashMap;
+  use std::sync::atomic::{AtomicUsize, Ordering};
+  use std::sync::{Arc, Mutex};
+  use tokio::io::{AsyncReadExt, AsyncWriteExt};
+  use tokio::net::TcpListener;
+
+  fn sample(timestamp: i64, values: &[(MetricKind, f32)]) -> MetricSample {
+    MetricSample {
+      timestamp,
+      values: values.iter().copied().collect::<HashMap<_, _>>(),
+    }
+  }
+
+  fn lines(count: usize) -> Vec<String> {
+    (0..count).map(|index| format!("m v={}", index)).collect()
+  }
+
+  ///
+  /// テストごとに異なるパスを使う `DiskBuffer`（終了時にファイルを削除）
+  ///
+  struct TempBuffer(DiskBuffer);
+
+  impl TempBuffer {
+    fn new() -> Self {
+      static COUNTER: AtomicUsize = AtomicUsize::new(0);
+      let path = std::env::temp_dir().join(format!(
+        "influx-buffer-{}-{}",
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::SeqCst)
+      ));
+      Self(DiskBuffer::new(path.join(BUFFER_FILENAME)))
+    }
+  }
+
+  impl Drop for TempBuffer {
+    fn drop(&mut self) {
+      if let Some(parent) = self.0.path.parent() {
+        let _ = fs::remove_dir_all(parent);
+      }
+    }
+  }
+
+  ///
+  /// 指定したステータスコードを順に返すローカルの HTTP サーバー
+  ///
+  /// - 受信したリクエスト（ヘッダーとボディ）を記録する
+  /// - ステータスコードを使い切った後は 204 を返す
+  ///
+  async fn mock_server(statuses: Vec<u16>) -> (String, Arc<Mutex<Vec<String>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/api/v2/write", listener.local_addr().unwrap());
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let received = requests.clone();
+
+    tokio::spawn(async move {
+      let mut statuses = statuses.into_iter();
+      loop {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let request = read_request(&mut stream).await;
+        received.lock().unwrap().push(request);
+
+        let status = statuses.next().unwrap_or(204);
+        let response = format!(
+          "HTTP/1.1 {} Mock\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+          status
+        );
+        stream.write_all(response.as_bytes()).await.unwrap();
+        stream.shutdown().await.ok();
+      }
+    });
+
+    (url, requests)
+  }
+
+  async fn read_request(stream: &mut tokio::net::TcpStream) -> String {
+    let mut request = Vec::new();
+    let mut buffer = [0u8; 1024];
+
+    loop {
+      let read = stream.read(&mut buffer).await.unwrap();
+      if read == 0 {
+        break;
+      }
+      request.extend_from_slice(&buffer[..read]);
+
+      let text = String::from_utf8_lossy(&request).to_string();
+      if let Some(header_end) = text.find("\r\n\r\n") {
+        let content_length = text[..header_end]
+          .lines()
+          .find_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            name
+              .eq_ignore_ascii_case("content-length")
+              .then(|| value.trim().parse::<usize>().ok())?
+          })
+          .unwrap_or(0);
+        if request.len() >= header_end + 4 + content_length {
+          return text;
+        }
+      }
+    }
+
+    String::from_utf8_lossy(&request).to_string()
+  }
+
+  fn body(request: &str) -> &str {
+    request.split_once("\r\n\r\n").map_or("", |(_, body)| body)
+  }
+
+  fn http_settings(url: &str, batch_size: usize) -> InfluxSettings {
+    InfluxSettings {
+      enabled: true,
+      url: url.to_string(),
+      token: Some("secret".to_string()),
+      batch_size,
+      ..InfluxSettings::default()
+    }
+  }
+
+  #[test]
+  fn to_line_sorts_fields_and_uses_nanoseconds() {
+    let tags = BTreeMap::from([("host".to_string(), "pc".to_string())]);
+    let line = to_line(
+      &sample(
+        1_700_000_000_123,
+        &[
+          (MetricKind::MemoryUsage, 40.5),
+          (MetricKind::CpuUsage, 12.0),
+        ],
+      ),
+      "hardware_monitor",
+      &tags,
+    );
+
+    assert_eq!(
+      line.as_deref(),
+      Some("hardware_monitor,host=pc cpuUsage=12,memoryUsage=40.5 1700000000123000000")
+    );
+  }
+
+  #[test]
+  fn to_line_escapes_measurement_and_tags() {
+    let tags = BTreeMap::from([
+      ("a key".to_string(), "x=1,y 2".to_string()),
+      ("path".to_string(), "C:\\dir\\".to_string()),
+      ("note".to_string(), "line1\nline2".to_string()),
+    ]);
+    let line = to_line(
+      &sample(1, &[(MetricKind::CpuUsage, 1.0)]),
+      "my measurement,x",
+      &tags,
+    )
+    .unwrap();
+
+    assert_eq!(
+      line,
+      "my\\ measurement\\,x,a\\ key=x\\=1\\,y\\ 2,note=line1\\nline2,path=C:\\\\dir\\\\ cpuUsage=1 1000000"
+    );
+    assert!(!line.contains('\n'));
+  }
+
+  #[test]
+  fn to_line_skips_non_finite_values() {
+    let tags = BTreeMap::new();
+
+    assert_eq!(
+      to_line(
+        &sample(
+          1,
+          &[
+            (MetricKind::CpuUsage, f32::NAN),
+            (MetricKind::GpuUsage, 5.0)
+          ]
+        ),
+        "m",
+        &tags
+      )
+      .as_deref(),
+      Some("m gpuUsage=5 1000000")
+    );
+    assert_eq!(
+      to_line(
+        &sample(1, &[(MetricKind::CpuUsage, f32::INFINITY)]),
+        "m",
+        &tags
+      ),
+      None
+    );
+    assert_eq!(to_line(&sample(1, &[]), "m", &tags), None);
+  }
+
+  #[test]
+  fn pack_lines_splits_by_max_bytes() {
+    let lines = vec!["a".repeat(4), "b".repeat(4), "c".repeat(4)];
+
+    assert_eq!(
+      pack_lines(&lines, 9),
+      vec!["aaaa\nbbbb".to_string(), "cccc".to_string()]
+    );
+    assert_eq!(
+      pack_lines(&lines, 100),
+      vec!["aaaa\nbbbb\ncccc".to_string()]
+    );
+    assert!(pack_lines(&[], 100).is_empty());
+  }
+
+  #[test]
+  fn pack_lines_keeps_oversized_line_in_own_packet() {
+    let lines = vec!["a".to_string(), "b".repeat(10), "c".to_string()];
+
+    assert_eq!(
+      pack_lines(&lines, 5),
+      vec!["a".to_string(), "b".repeat(10), "c".to_string()]
+    );
+  }
+
+  #[test]
+  fn disk_buffer_round_trip() {
+    let buffer = TempBuffer::new();
+    assert!(buffer.0.is_empty());
+    assert!(buffer.0.load().is_empty());
+
+    buffer.0.replace(&lines(3)).unwrap();
+    assert!(!buffer.0.is_empty());
+    assert_eq!(buffer.0.load(), lines(3));
+
+    buffer.0.replace(&lines(1)).unwrap();
+    assert_eq!(buffer.0.load(), lines(1));
+
+    buffer.0.replace(&[]).unwrap();
+    assert!(buffer.0.is_empty());
+    // 既に削除されていてもエラーにしない
+    buffer.0.replace(&[]).unwrap();
+  }
+
+  #[tokio::test]
+  async fn send_batch_posts_lines_with_token() {
+    let (url, requests) = mock_server(vec![]).await;
+
+    send_batch(&reqwest::Client::new(), &http_settings(&url, 10), &lines(2))
+      .await
+      .unwrap();
+
+    let requests = requests.lock().unwrap();
+    assert_eq!(requests.len(), 1);
+    assert!(requests[0].starts_with("POST /api/v2/write "));
+    assert!(requests[0]
+      .to_ascii_lowercase()
+      .contains("authorization: token secret"));
+    assert_eq!(body(&requests[0]), "m v=0\nm v=1");
+  }
+
+  #[tokio::test]
+  async fn send_batch_classifies_errors() {
+    let client = reqwest::Client::new();
+
+    for (status, retryable) in [
+      (500, true),
+      (503, true),
+      (429, true),
+      (400, false),
+      (401, false),
+    ] {
+      let (url, _) = mock_server(vec![status]).await;
+      let error = send_batch(&client, &http_settings(&url, 10), &lines(1))
+        .await
+        .unwrap_err();
+      assert_eq!(error.retryable, retryable, "HTTP {}", status);
+    }
+
+    // 割り当てられたポートを解放し、接続できない URL にする
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/api/v2/write", listener.local_addr().unwrap());
+    drop(listener);
+    let error = send_batch(&client, &http_settings(&url, 10), &lines(1))
+      .await
+      .unwrap_err();
+    assert!(error.retryable);
+  }
+
+  #[tokio::test]
+  async fn flush_buffers_lines_on_retryable_error() {
+    let (url, requests) = mock_server(vec![204, 503]).await;
+    let buffer = TempBuffer::new();
+    let client = reqwest::Client::new();
+    let settings = http_settings(&url, 2);
+
+    flush(&client, &settings, &buffer.0, lines(5)).await;
+
+    // 最初のまとまりのみ送信でき、残りは保存する
+    assert_eq!(requests.lock().unwrap().len(), 2);
+    assert_eq!(buffer.0.load(), lines(5)[2..].to_vec());
+
+    // 保存した行から順に再送する
+    flush(&client, &settings, &buffer.0, vec!["m v=5".to_string()]).await;
+
+    let requests = requests.lock().unwrap();
+    assert_eq!(body(&requests[2]), "m v=2\nm v=3");
+    assert_eq!(body(&requests[3]), "m v=4\nm v=5");
+    assert!(buffer.0.is_empty());
+  }
+
+  #[tokio::test]
+  async fn flush_drops_batch_on_client_error() {
+    let (url, requests) = mock_server(vec![400]).await;
+    let buffer = TempBuffer::new();
+
+    flush(
+      &reqwest::Client::new(),
+      &http_settings(&url, 2),
+      &buffer.0,
+      lines(4),
+    )
+    .await;
+
+    // 受け付けられないまとまりを破棄し、後続のまとまりは送信する
+    let requests = requests.lock().unwrap();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(body(&requests[1]), "m v=2\nm v=3");
+    assert!(buffer.0.is_empty());
+  }
+
+  #[tokio::test]
+  async fn flush_limits_buffered_lines() {
+    let (url, _) = mock_server(vec![500]).await;
+    let buffer = TempBuffer::new();
+    let settings = InfluxSettings {
+      max_buffered_lines: 3,
+      ..http_settings(&url, 10)
+    };
+
+    flush(&reqwest::Client::new(), &settings, &buffer.0, lines(5)).await;
+
+    // 超えた分は古い順に削除する
+    assert_eq!(buffer.0.load(), lines(5)[2..].to_vec());
+  }
+
+  #[tokio::test]
+  async fn send_batch_over_udp() {
+    let receiver = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let settings = InfluxSettings {
+      transport: InfluxTransport::Udp,
+      udp_address: receiver.local_addr().unwrap().to_string(),
+      ..InfluxSettings::default()
+    };
+    let lines: Vec<String> = (0..40).map(|index| format!("m v={:0>40}", index)).collect();
+
+    send_batch(&reqwest::Client::new(), &settings, &lines)
+      .await
+      .unwrap();
+
+    let mut received = Vec::new();
+    let mut packet = vec![0u8; 65536];
+    while received.len() < lines.len() {
+      let len = tokio::time::timeout(Duration::from_secs(5), receiver.recv(&mut packet))
+        .await
+        .unwrap()
+        .unwrap();
+      assert!(len <= MAX_UDP_PAYLOAD);
+      let text = String::from_utf8(packet[..len].to_vec()).unwrap();
+      received.extend(text.lines().map(str::to_string));
+    }
+    assert_eq!(received, lines);
+  }
+}
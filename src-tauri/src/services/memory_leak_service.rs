@@ -0,0 +1,418 @@
+use crate::services::process_service;
+use crate::utils::formatter;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::collections::VecDeque;
+use sysinfo::{Pid, Process};
+
+///
+/// メモリリークの疑いを検出したときのイベント名
+///
+pub const MEMORY_LEAK_DETECTED_EVENT: &str = "memory_leak_detected";
+
+///
+/// 解析用にメモリ使用量を記録する間隔（秒）
+///
+const SAMPLE_INTERVAL_SECS: i64 = 10;
+
+///
+/// 回帰分析に必要な最小のサンプル数
+///
+const MIN_SAMPLE_COUNT: usize = 6;
+
+///
+/// 解析対象とする期間の上限（分）
+///
+/// - プロセスごとに `SAMPLE_INTERVAL_SECS` 秒ごとのサンプルを保持するため、メモリ使用量を抑える
+///
+const MAX_WINDOW_MINUTES: u64 = 24 * 60;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct MemoryLeakSettings {
+  pub enabled: bool,
+  pub window_minutes: u64,         // 解析対象とする期間
+  pub min_growth_mb_per_hour: f64, // 回帰直線の傾きの閾値
+  pub min_r_squared: f64,          // 決定係数の閾値（1 に近いほど単調に増加している）
+}
+
+impl Default for MemoryLeakSettings {
+  fn default() -> Self {
+    Self {
+      enabled: true,
+      window_minutes: 30,
+      min_growth_mb_per_hour: 10.0,
+      min_r_squared: 0.8,
+    }
+  }
+}
+
+impl MemoryLeakSettings {
+  ///
+  /// ## 設定の内容を検証
+  ///
+  pub fn validate(&self) -> Result<(), String> {
+    if !(1..=MAX_WINDOW_MINUTES).contains(&self.window_minutes) {
+      return Err(format!(
+        "Window must be between 1 and {} minutes",
+        MAX_WINDOW_MINUTES
+      ));
+    }
+
+    Ok(())
+  }
+
+  ///
+  /// ## 解析対象とする期間（秒）
+  ///
+  /// - 設定ファイルを直接編集された場合も桁あふれしないよう、上限で丸める
+  ///
+  fn window_secs(&self) -> i64 {
+    self
+      .window_minutes
+      .clamp(1, MAX_WINDOW_MINUTES)
+      .checked_mul(60)
+      .and_then(|secs| i64::try_from(secs).ok())
+      .unwrap_or(i64::MAX)
+  }
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryLeakSuspect {
+  pub pid: u32,
+  pub name: String,
+  pub start_time: u64,
+  pub growth_mb_per_hour: f64,
+  pub r_squared: f64,
+  pub current_memory_mb: f32,
+  pub observed_secs: i64, // 解析に使用したサンプルの期間
+  pub sample_count: usize,
+  pub detected_at: i64, // 初めて検出した時刻（UNIX エポックからの秒）
+}
+
+///
+/// PID の再利用を区別するため、開始時刻と組み合わせてプロセスを識別する
+///
+type ProcessKey = (u32, u64);
+
+#[derive(Debug, Default)]
+struct MemorySamples {
+  name: String,
+  points: VecDeque<(i64, f32)>, // (計測時刻, MB)
+}
+
+///
+/// プロセスごとのメモリ使用量を長期間記録し、単調な増加を検出する
+///
+#[derive(Debug, Default)]
+pub struct MemoryLeakDetector {
+  samples: HashMap<ProcessKey, MemorySamples>,
+  suspects: HashMap<ProcessKey, MemoryLeakSuspect>,
+  last_sampled_at: Option<i64>,
+}
+
+impl MemoryLeakDetector {
+  ///
+  /// ## メモリ使用量を記録して解析
+  ///
+  /// - param now: `i64` 現在時刻（UNIX エポックからの秒）
+  /// - return: `Vec<MemoryLeakSuspect>` 今回新たに検出したプロセス
+  /// - `SAMPLE_INTERVAL_SECS` 秒に満たない間隔で呼ばれた場合は何もしない
+  /// - スレッドのエントリは記録しない（同じプロセスを重複して検出しないため）
+  ///
+  pub fn update(
+    &mut self,
+    now: i64,
+    processes: &HashMap<Pid, Process>,
+    settings: &MemoryLeakSettings,
+  ) -> Vec<MemoryLeakSuspect> {
+    if let Some(last) = self.last_sampled_at {
+      if now - last < SAMPLE_INTERVAL_SECS {
+        return vec![];
+      }
+    }
+    self.last_sampled_at = Some(now);
+
+    let window_secs = settings.window_secs();
+    let mut active = HashSet::with_capacity(processes.len());
+
+    for process in processes.values() {
+      if process_service::is_thread(process) {
+        continue;
+      }
+
+      let key = (process.pid().as_u32(), process.start_time());
+      let samples = self.samples.entry(key).or_default();
+
+      samples.name = process.name().to_string_lossy().into_owned();
+      samples
+        .points
+        .push_back((now, formatter::bytes_to_mb(process.memory())));
+
+      while let Some(&(sampled_at, _)) = samples.points.front() {
+        if now - sampled_at <= window_secs {
+          break;
+        }
+        samples.points.pop_front();
+      }
+
+      active.insert(key);
+    }
+
+    // 終了したプロセスのデータは削除する
+    self.samples.retain(|key, _| active.contains(key));
+    self.suspects.retain(|key, _| active.contains(key));
+
+    let mut detected = Vec::new();
+
+    for (key, samples) in &self.samples {
+      let suspect = analyze(*key, samples, window_secs, settings, now);
+
+      match suspect {
+        Some(suspect) => match self.suspects.get_mut(key) {
+          Some(existing) => {
+            // 初回検出時刻は維持したまま最新の値に更新する
+            *existing = MemoryLeakSuspect {
+              detected_at: existing.detected_at,
+              ..suspect
+            };
+          }
+          None => {
+            detected.push(suspect.clone());
+            self.suspects.insert(*key, suspect);
+          }
+        },
+        None => {
+          self.suspects.remove(key);
+        }
+      }
+    }
+
+    detected
+  }
+
+  ///
+  /// ## 現在リークが疑われるプロセスを取得（増加量の大きい順）
+  ///
+  pub fn suspects(&self) -> Vec<MemoryLeakSuspect> {
+    let mut suspects: Vec<MemoryLeakSuspect> = self.suspects.values().cloned().collect();
+    suspects.sort_by(|a, b| {
+      b.growth_mb_per_hour
+        .partial_cmp(&a.growth_mb_per_hour)
+        .unwrap_or(Ordering::Equal)
+    });
+    suspects
+  }
+}
+
+///
+/// ## プロセスのサンプルを解析
+///
+/// - 期間の半分以上のデータが揃うまでは判定しない（起動直後の増加を誤検出しないため）
+///
+fn analyze(
+  (pid, start_time): ProcessKey,
+  samples: &MemorySamples,
+  window_secs: i64,
+  settings: &MemoryLeakSettings,
+  now: i64,
+) -> Option<MemoryLeakSuspect> {
+  let (first, _) = *samples.points.front()?;
+  let (last, current) = *samples.points.back()?;
+  let observed_secs = last - first;
+
+  if samples.points.len() < MIN_SAMPLE_COUNT
+    || observed_secs.saturating_mul(2) < window_secs
+  {
+    return None;
+  }
+
+  let points: Vec<(f64, f64)> = samples
+    .points
+    .iter()
+    .map(|&(sampled_at, mb)| ((sampled_at - first) as f64, mb as f64))
+    .collect();
+  let (slope, r_squared) = linear_regression(&points)?;
+
+  let growth_mb_per_hour = slope * 3600.0;
+
+  if growth_mb_per_hour < settings.min_growth_mb_per_hour
+    || r_squared < settings.min_r_squared
+  {
+    return None;
+  }
+
+  Some(MemoryLeakSuspect {
+    pid,
+    name: samples.name.clone(),
+    start_time,
+    growth_mb_per_hour: (growth_mb_per_hour * 10.0).round() / 10.0,
+    r_squared: (r_squared * 1000.0).round() / 1000.0,
+    current_memory_mb: current,
+    observed_secs,
+    sample_count: samples.points.len(),
+    detected_at: now,
+  })
+}
+
+///
+/// ## 最小二乗法で回帰直線の傾きと決定係数（R²）を計算
+///
+/// - return: `Option<(f64, f64)>` 傾きと決定係数（x の分散が 0 の場合は `None`）
+/// - y が一定の場合は傾き 0、決定係数 0 とする
+///
+pub fn linear_regression(points: &[(f64, f64)]) -> Option<(f64, f64)> {
+  if points.len() < 2 {
+    return None;
+  }
+
+  let n = points.len() as f64;
+  let mean_x = points.iter().map(|(x, _)| x).sum::<f64>() / n;
+  let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / n;
+
+  let mut sxx = 0.0;
+  let mut sxy = 0.0;
+  let mut syy = 0.0;
+
+  for (x, y) in points {
+    let dx = x - mean_x;
+    let dy = y - mean_y;
+    sxx += dx * dx;
+    sxy += dx * dy;
+    syy += dy * dy;
+  }
+
+  if sxx == 0.0 {
+    return None;
+  }
+
+  let slope = sxy / sxx;
+  let r_squared = if syy == 0.0 {
+    0.0
+  } else {
+    (sxy * sxy) / (sxx * syy)
+  };
+
+  Some((slope, r_squared))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn settings(window_minutes: u64) -> MemoryLeakSettings {
+    MemoryLeakSettings {
+      window_minutes,
+      ..Default::default()
+    }
+  }
+
+  ///
+  /// `start` 秒から `SAMPLE_INTERVAL_SECS` 秒ごとに `mb_per_sample` MB ずつ増えるサンプル
+  ///
+  fn growing_samples(start: i64, count: usize, mb_per_sample: f32) -> MemorySamples {
+    MemorySamples {
+      name: "leaky".to_string(),
+      points: (0..count)
+        .map(|index| {
+          (
+            start + index as i64 * SAMPLE_INTERVAL_SECS,
+            100.0 + index as f32 * mb_per_sample,
+          )
+        })
+        .collect(),
+    }
+  }
+
+  #[test]
+  fn linear_regression_fits_known_series() {
+    let points = [(0.0, 1.0), (1.0, 3.0), (2.0, 5.0), (3.0, 7.0)];
+
+    let (slope, r_squared) = linear_regression(&points).unwrap();
+
+    assert!((slope - 2.0).abs() < 1e-9);
+    assert!((r_squared - 1.0).abs() < 1e-9);
+  }
+
+  #[test]
+  fn linear_regression_of_noisy_series() {
+    let points = [(0.0, 2.0), (1.0, 1.0), (2.0, 4.0), (3.0, 3.0)];
+
+    let (slope, r_squared) = linear_regression(&points).unwrap();
+
+    // 傾き 0.6、決定係数 0.36
+    assert!((slope - 0.6).abs() < 1e-9);
+    assert!((r_squared - 0.36).abs() < 1e-9);
+  }
+
+  #[test]
+  fn linear_regression_degenerate_inputs() {
+    assert_eq!(linear_regression(&[]), None);
+    assert_eq!(linear_regression(&[(1.0, 1.0)]), None);
+    assert_eq!(linear_regression(&[(1.0, 1.0), (1.0, 5.0)]), None);
+    assert_eq!(
+      linear_regression(&[(0.0, 5.0), (1.0, 5.0), (2.0, 5.0)]),
+      Some((0.0, 0.0))
+    );
+  }
+
+  #[test]
+  fn window_secs_is_clamped() {
+    assert_eq!(settings(0).window_secs(), 60);
+    assert_eq!(settings(30).window_secs(), 30 * 60);
+    assert_eq!(
+      settings(u64::MAX).window_secs(),
+      MAX_WINDOW_MINUTES as i64 * 60
+    );
+    assert!(settings(0).validate().is_err());
+    assert!(settings(MAX_WINDOW_MINUTES + 1).validate().is_err());
+    assert!(settings(MAX_WINDOW_MINUTES).validate().is_ok());
+  }
+
+  #[test]
+  fn analyze_detects_steady_growth() {
+    // 10秒ごとに 0.1 MB 増加 = 36 MB/h
+    let samples = growing_samples(1_000, 31, 0.1);
+    let settings = settings(5);
+
+    let suspect =
+      analyze((42, 7), &samples, settings.window_secs(), &settings, 1_300).unwrap();
+
+    assert_eq!(suspect.pid, 42);
+    assert_eq!(suspect.start_time, 7);
+    assert_eq!(suspect.name, "leaky");
+    assert_eq!(suspect.growth_mb_per_hour, 36.0);
+    assert_eq!(suspect.r_squared, 1.0);
+    assert_eq!(suspect.observed_secs, 300);
+    assert_eq!(suspect.sample_count, 31);
+    assert_eq!(suspect.detected_at, 1_300);
+  }
+
+  #[test]
+  fn analyze_waits_for_half_of_window() {
+    let settings = settings(30);
+
+    // 6分間（期間の半分に満たない）
+    let samples = growing_samples(0, 37, 0.1);
+    assert!(analyze((1, 0), &samples, settings.window_secs(), &settings, 360).is_none());
+
+    // 15分間
+    let samples = growing_samples(0, 91, 0.1);
+    assert!(analyze((1, 0), &samples, settings.window_secs(), &settings, 900).is_some());
+  }
+
+  #[test]
+  fn analyze_ignores_slow_or_flat_growth() {
+    let settings = settings(5);
+
+    // 10秒ごとに 0.01 MB = 3.6 MB/h（閾値 10 MB/h 未満）
+    let samples = growing_samples(0, 31, 0.01);
+    assert!(analyze((1, 0), &samples, settings.window_secs(), &settings, 300).is_none());
+
+    let samples = growing_samples(0, 31, 0.0);
+    assert!(analyze((1, 0), &samples, settings.window_secs(), &settings, 300).is_none());
+  }
+}
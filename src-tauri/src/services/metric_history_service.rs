@@ -0,0 +1,82 @@
+use crate::enums::metric::MetricKind;
+use std::collections::HashMap;
+use std::collections::VecDeque;
+
+///
+/// 保持するサンプル数（1秒ごとの計測で 1時間分）
+///
+pub const METRIC_HISTORY_CAPACITY: usize = 3600;
+
+#[derive(Debug, Clone)]
+pub struct MetricSample {
+  pub timestamp: i64, // UNIX エポックからのミリ秒
+  pub values: HashMap<MetricKind, f32>,
+}
+
+///
+/// サンプリングのループで計測したシステム全体の指標の履歴（計測時刻付き）
+///
+#[derive(Debug, Default)]
+pub struct MetricHistory {
+  samples: VecDeque<MetricSample>,
+}
+
+impl MetricHistory {
+  ///
+  /// ## 計測値を追加（`METRIC_HISTORY_CAPACITY` を超えた分は古い順に削除）
+  ///
+  pub fn push(&mut self, timestamp: i64, values: &HashMap<MetricKind, f32>) {
+    if self.samples.len() >= METRIC_HISTORY_CAPACITY {
+      self.samples.pop_front();
+    }
+    self.samples.push_back(MetricSample {
+      timestamp,
+      values: values.clone(),
+    });
+  }
+
+  ///
+  /// ## 指標の履歴を取得（古い順）
+  ///
+  /// - param metric: `MetricKind` 取得する指標（計測できなかった時刻は含まない）
+  /// - param from: `i64` 指定した時刻以降のみ取得（UNIX エポックからのミリ秒）
+  ///
+  pub fn points(&self, metric: MetricKind, from: i64) -> Vec<(i64, f32)> {
+    self
+      .samples
+      .iter()
+      .filter(|sample| sample.timestamp >= from)
+      .filter_map(|sample| {
+        sample
+          .values
+          .get(&metric)
+          .map(|value| (sample.timestamp, *value))
+      })
+      .collect()
+  }
+
+  ///
+  /// ## 指定した期間の計測値を取得（古い順）
+  ///
+  /// - param from: `Option<i64>` 開始時刻（UNIX エポックからのミリ秒、`None` の場合は最も古いサンプルから）
+  /// - param to: `Option<i64>` 終了時刻（`None` の場合は最新のサンプルまで）
+  ///
+  pub fn range(&self, from: Option<i64>, to: Option<i64>) -> Vec<MetricSample> {
+    self
+      .samples
+      .iter()
+      .filter(|sample| {
+        from.map_or(true, |from| sample.timestamp >= from)
+          && to.map_or(true, |to| sample.timestamp <= to)
+      })
+      .cloned()
+      .collect()
+  }
+
+  ///
+  /// ## 最新の計測値を取得
+  ///
+  pub fn latest(&self) -> Option<MetricSample> {
+    self.samples.back().cloned()
+  }
+}
@@ -1,2 +1,21 @@
+pub mod alert_command_service;
+pub mod alert_history_service;
+pub mod alert_service;
+pub mod anomaly_service;
+pub mod cgroup_service;
+pub mod export_service;
 pub mod graphic_service;
+pub mod influx_service;
+pub mod memory_leak_service;
+pub mod metric_history_service;
+pub mod network_connection_service;
+pub mod otlp_service;
+pub mod process_group_service;
+pub mod process_service;
+pub mod process_watch_service;
+pub mod procfs_service;
+pub mod prometheus_service;
+pub mod silence_service;
 pub mod system_info_service;
+pub mod thread_service;
+pub mod webhook_service;
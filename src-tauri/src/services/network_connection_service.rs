@@ -0,0 +1,489 @@
+use crate::{log_debug, log_internal, log_warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::path::Path;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum SocketProtocol {
+  Tcp,
+  Tcp6,
+  Udp,
+  Udp6,
+  Unix,
+}
+
+impl SocketProtocol {
+  pub const ALL: [SocketProtocol; 5] = [
+    SocketProtocol::Tcp,
+    SocketProtocol::Tcp6,
+    SocketProtocol::Udp,
+    SocketProtocol::Udp6,
+    SocketProtocol::Unix,
+  ];
+
+  ///
+  /// ## `/proc/net` 配下のファイル名
+  ///
+  fn file_name(self) -> &'static str {
+    match self {
+      SocketProtocol::Tcp => "tcp",
+      SocketProtocol::Tcp6 => "tcp6",
+      SocketProtocol::Udp => "udp",
+      SocketProtocol::Udp6 => "udp6",
+      SocketProtocol::Unix => "unix",
+    }
+  }
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkConnection {
+  pub protocol: SocketProtocol,
+  pub local_address: String, // Unix ドメインソケットの場合はパス
+  pub local_port: Option<u16>,
+  pub remote_address: Option<String>,
+  pub remote_port: Option<u16>,
+  pub state: String,
+  pub inode: u64,
+  pub pid: Option<u32>,
+  pub process_name: Option<String>,
+}
+
+///
+/// ## ソケットの一覧を取得し、所有プロセスを紐づける
+///
+/// - param proc_root: `&Path` procfs のマウントポイント
+/// - param protocols: `&[SocketProtocol]` 取得するプロトコル
+///
+pub fn get_connections(
+  proc_root: &Path,
+  protocols: &[SocketProtocol],
+) -> Result<Vec<NetworkConnection>, String> {
+  log_debug!("start", "get_connections", None::<&str>);
+
+  let mut connections = Vec::new();
+
+  for protocol in protocols {
+    let path = proc_root.join("net").join(protocol.file_name());
+
+    let content = match fs::read_to_string(&path) {
+      Ok(content) => content,
+      Err(e) => {
+        // IPv6 が無効な環境などではファイルが存在しない
+        log_warn!(
+          "read_failed",
+          "get_connections",
+          Some(format!("{}: {}", path.display(), e))
+        );
+        continue;
+      }
+    };
+
+    match protocol {
+      SocketProtocol::Unix => connections.extend(parse_unix_sockets(&content)),
+      _ => connections.extend(parse_inet_sockets(&content, *protocol)),
+    }
+  }
+
+  if connections.is_empty() && !proc_root.join("net").exists() {
+    return Err(format!("{}/net is not available", proc_root.display()));
+  }
+
+  let owners = map_socket_inodes(proc_root);
+
+  for connection in connections.iter_mut() {
+    if let Some((pid, name)) = owners.get(&connection.inode) {
+      connection.pid = Some(*pid);
+      connection.process_name = Some(name.clone());
+    }
+  }
+
+  log_debug!("end", "get_connections", None::<&str>);
+
+  Ok(connections)
+}
+
+///
+/// ## 指定したポートで待ち受けているソケットを取得
+///
+/// - TCP は `LISTEN` 状態、UDP は接続先を持たないソケットを対象とする
+///
+pub fn get_listeners(
+  proc_root: &Path,
+  port: u16,
+) -> Result<Vec<NetworkConnection>, String> {
+  let connections = get_connections(
+    proc_root,
+    &[
+      SocketProtocol::Tcp,
+      SocketProtocol::Tcp6,
+      SocketProtocol::Udp,
+      SocketProtocol::Udp6,
+    ],
+  )?;
+
+  Ok(
+    connections
+      .into_iter()
+      .filter(|connection| connection.local_port == Some(port))
+      .filter(|connection| connection.state == "LISTEN" || connection.state == "UNCONN")
+      .collect(),
+  )
+}
+
+///
+/// ## `/proc/net/{tcp,tcp6,udp,udp6}` を解析
+///
+/// - 各行は `sl local_address rem_address st ... uid timeout inode ...` の形式
+///
+pub fn parse_inet_sockets(
+  content: &str,
+  protocol: SocketProtocol,
+) -> Vec<NetworkConnection> {
+  let is_udp = matches!(protocol, SocketProtocol::Udp | SocketProtocol::Udp6);
+
+  content
+    .lines()
+    .skip(1) // ヘッダー行
+    .filter_map(|line| {
+      let fields: Vec<&str> = line.split_whitespace().collect();
+      if fields.len() < 10 {
+        return None;
+      }
+
+      let (local_address, local_port) = parse_socket_address(fields[1])?;
+      let (remote_address, remote_port) = parse_socket_address(fields[2])?;
+      let state = tcp_state_name(fields[3], is_udp);
+      let inode = fields[9].parse::<u64>().ok()?;
+
+      Some(NetworkConnection {
+        protocol,
+        local_address,
+        local_port: Some(local_port),
+        remote_address: Some(remote_address),
+        remote_port: Some(remote_port),
+        state: state.to_string(),
+        inode,
+        pid: None,
+        process_name: None,
+      })
+    })
+    .collect()
+}
+
+///
+/// ## `/proc/net/unix` を解析
+///
+/// - 各行は `Num RefCount Protocol Flags Type St Inode [Path]` の形式
+///
+pub fn parse_unix_sockets(content: &str) -> Vec<NetworkConnection> {
+  // 待ち受け中のソケットに立つフラグ（__SO_ACCEPTCON）
+  const SO_ACCEPTCON: u32 = 0x0001_0000;
+
+  content
+    .lines()
+    .skip(1) // ヘッダー行
+    .filter_map(|line| {
+      let fields: Vec<&str> = line.split_whitespace().collect();
+      if fields.len() < 7 {
+        return None;
+      }
+
+      let flags = u32::from_str_radix(fields[3], 16).ok()?;
+      let state = if flags & SO_ACCEPTCON != 0 {
+        "LISTEN"
+      } else {
+        match fields[5] {
+          "01" => "UNCONNECTED",
+          "02" => "CONNECTING",
+          "03" => "CONNECTED",
+          "04" => "DISCONNECTING",
+          _ => "UNKNOWN",
+        }
+      };
+      let inode = fields[6].parse::<u64>().ok()?;
+      // パスには空白が含まれることがある
+      let path = fields[7..].join(" ");
+
+      Some(NetworkConnection {
+        protocol: SocketProtocol::Unix,
+        local_address: path,
+        local_port: None,
+        remote_address: None,
+        remote_port: None,
+        state: state.to_string(),
+        inode,
+        pid: None,
+        process_name: None,
+      })
+    })
+    .collect()
+}
+
+///
+/// ## `ADDRESS:PORT` 形式の16進表記を解析
+///
+/// - アドレスは 32bit ごとにホストバイトオーダー（リトルエンディアン）で格納されている
+///
+pub fn parse_socket_address(value: &str) -> Option<(String, u16)> {
+  let (address, port) = value.split_once(':')?;
+  let port = u16::from_str_radix(port, 16).ok()?;
+
+  let address = match address.len() {
+    8 => {
+      let raw = u32::from_str_radix(address, 16).ok()?;
+      Ipv4Addr::from(raw.swap_bytes()).to_string()
+    }
+    32 => {
+      let mut octets = [0u8; 16];
+      for (i, chunk) in octets.chunks_mut(4).enumerate() {
+        let word = u32::from_str_radix(&address[i * 8..i * 8 + 8], 16).ok()?;
+        chunk.copy_from_slice(&word.to_le_bytes());
+      }
+      Ipv6Addr::from(octets).to_string()
+    }
+    _ => return None,
+  };
+
+  Some((address, port))
+}
+
+///
+/// ## TCP の状態コードを名前に変換
+///
+/// - UDP には状態がないため、未接続のソケット（`07`）は `UNCONN` とする
+///
+fn tcp_state_name(code: &str, is_udp: bool) -> &'static str {
+  match code {
+    "01" => "ESTABLISHED",
+    "02" => "SYN_SENT",
+    "03" => "SYN_RECV",
+    "04" => "FIN_WAIT1",
+    "05" => "FIN_WAIT2",
+    "06" => "TIME_WAIT",
+    "07" if is_udp => "UNCONN",
+    "07" => "CLOSE",
+    "08" => "CLOSE_WAIT",
+    "09" => "LAST_ACK",
+    "0A" => "LISTEN",
+    "0B" => "CLOSING",
+    _ => "UNKNOWN",
+  }
+}
+
+///
+/// ## ソケットの inode と所有プロセスの対応表を作成
+///
+/// - `/proc/<pid>/fd/*` のリンク先 `socket:[<inode>]` から求める
+/// - 権限不足で読めないプロセスは無視する
+///
+pub fn map_socket_inodes(proc_root: &Path) -> HashMap<u64, (u32, String)> {
+  let mut owners = HashMap::new();
+
+  let entries = match fs::read_dir(proc_root) {
+    Ok(entries) => entries,
+    Err(e) => {
+      log_warn!("read_dir_failed", "map_socket_inodes", Some(e.to_string()));
+      return owners;
+    }
+  };
+
+  for entry in entries.flatten() {
+    let pid = match entry.file_name().to_string_lossy().parse::<u32>() {
+      Ok(pid) => pid,
+      Err(_) => continue, // `net` や `self` などプロセス以外のエントリ
+    };
+
+    let fds = match fs::read_dir(entry.path().join("fd")) {
+      Ok(fds) => fds,
+      Err(_) => continue,
+    };
+
+    let name = fs::read_to_string(entry.path().join("comm"))
+      .map(|comm| comm.trim().to_string())
+      .unwrap_or_default();
+
+    for fd in fds.flatten() {
+      let inode = fs::read_link(fd.path())
+        .ok()
+        .and_then(|target| parse_socket_link(&target.to_string_lossy()));
+
+      if let Some(inode) = inode {
+        owners.entry(inode).or_insert_with(|| (pid, name.clone()));
+      }
+    }
+  }
+
+  owners
+}
+
+///
+/// ## `socket:[<inode>]` 形式のリンク先から inode を取り出す
+///
+pub fn parse_socket_link(target: &str) -> Option<u64> {
+  target
+    .strip_prefix("socket:[")?
+    .strip_suffix(']')?
+    .parse::<u64>()
+    .ok()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const TCP_FIXTURE: &str = "\
+  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+   0: 0100007F:0CEA 00000000:0000 0A 00000000:00000000 00:00000000 00000000   999        0 12345 1 0000000000000000 100 0 0 10 0
+   1: 0F02000A:A2C4 2E1EA8C0:01BB 01 00000000:00000000 02:00000A3C 00000000  1000        0 23456 2 0000000000000000 20 4 30 10 -1
+   2: malformed
+";
+
+  const TCP6_FIXTURE: &str = "\
+  sl  local_address                         remote_address                        st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+   0: 00000000000000000000000000000000:1F90 00000000000000000000000000000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 34567 1 0000000000000000 100 0 0 10 0
+   1: 00000000000000000000000001000000:0016 00000000000000000000000001000000:D431 01 00000000:00000000 00:00000000 00000000     0        0 45678 1 0000000000000000 20 4 0 10 -1
+";
+
+  const UDP_FIXTURE: &str = "\
+   sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode ref pointer drops
+  123: 00000000:14E9 00000000:0000 07 00000000:00000000 00:00000000 00000000   104        0 56789 2 0000000000000000 0
+";
+
+  const UNIX_FIXTURE: &str = "\
+Num       RefCount Protocol Flags    Type St Inode Path
+0000000000000000: 00000002 00000000 00010000 0001 01 11111 /run/dbus/system_bus_socket
+0000000000000000: 00000003 00000000 00000000 0001 03 22222
+0000000000000000: 00000002 00000000 00010000 0001 01 33333 /tmp/my app/control socket
+0000000000000000: 00000002 00000000 00000000 0002 01 44444 @/tmp/.X11-unix/X0
+0000000000000000: 00000002
+";
+
+  #[test]
+  fn parses_tcp_sockets() {
+    let connections = parse_inet_sockets(TCP_FIXTURE, SocketProtocol::Tcp);
+
+    assert_eq!(connections.len(), 2);
+
+    let listener = &connections[0];
+    assert_eq!(listener.protocol, SocketProtocol::Tcp);
+    assert_eq!(listener.local_address, "127.0.0.1");
+    assert_eq!(listener.local_port, Some(3306));
+    assert_eq!(listener.remote_address.as_deref(), Some("0.0.0.0"));
+    assert_eq!(listener.remote_port, Some(0));
+    assert_eq!(listener.state, "LISTEN");
+    assert_eq!(listener.inode, 12345);
+    assert_eq!(listener.pid, None);
+
+    let established = &connections[1];
+    assert_eq!(established.local_address, "10.0.2.15");
+    assert_eq!(established.local_port, Some(41668));
+    assert_eq!(established.remote_address.as_deref(), Some("192.168.30.46"));
+    assert_eq!(established.remote_port, Some(443));
+    assert_eq!(established.state, "ESTABLISHED");
+  }
+
+  #[test]
+  fn parses_tcp6_sockets() {
+    let connections = parse_inet_sockets(TCP6_FIXTURE, SocketProtocol::Tcp6);
+
+    assert_eq!(connections.len(), 2);
+    assert_eq!(connections[0].local_address, "::");
+    assert_eq!(connections[0].local_port, Some(8080));
+    assert_eq!(connections[0].state, "LISTEN");
+    assert_eq!(connections[1].local_address, "::1");
+    assert_eq!(connections[1].local_port, Some(22));
+    assert_eq!(connections[1].remote_address.as_deref(), Some("::1"));
+    assert_eq!(connections[1].remote_port, Some(54321));
+    assert_eq!(connections[1].inode, 45678);
+  }
+
+  #[test]
+  fn udp_without_peer_is_unconnected() {
+    let connections = parse_inet_sockets(UDP_FIXTURE, SocketProtocol::Udp);
+
+    assert_eq!(connections.len(), 1);
+    assert_eq!(connections[0].local_address, "0.0.0.0");
+    assert_eq!(connections[0].local_port, Some(5353));
+    assert_eq!(connections[0].state, "UNCONN");
+    assert_eq!(connections[0].inode, 56789);
+
+    // TCP の `07` は CLOSE
+    assert_eq!(tcp_state_name("07", false), "CLOSE");
+  }
+
+  #[test]
+  fn parses_unix_sockets() {
+    let connections = parse_unix_sockets(UNIX_FIXTURE);
+
+    assert_eq!(connections.len(), 4);
+
+    assert_eq!(connections[0].protocol, SocketProtocol::Unix);
+    assert_eq!(connections[0].local_address, "/run/dbus/system_bus_socket");
+    assert_eq!(connections[0].local_port, None);
+    assert_eq!(connections[0].state, "LISTEN");
+    assert_eq!(connections[0].inode, 11111);
+
+    assert_eq!(connections[1].local_address, "");
+    assert_eq!(connections[1].state, "CONNECTED");
+
+    assert_eq!(connections[2].local_address, "/tmp/my app/control socket");
+    assert_eq!(connections[2].state, "LISTEN");
+
+    assert_eq!(connections[3].local_address, "@/tmp/.X11-unix/X0");
+    assert_eq!(connections[3].state, "UNCONNECTED");
+  }
+
+  #[test]
+  fn parses_ipv4_socket_address() {
+    assert_eq!(
+      parse_socket_address("0100007F:0050"),
+      Some(("127.0.0.1".to_string(), 80))
+    );
+    assert_eq!(
+      parse_socket_address("00000000:FFFF"),
+      Some(("0.0.0.0".to_string(), 65535))
+    );
+  }
+
+  #[test]
+  fn parses_ipv6_socket_address() {
+    assert_eq!(
+      parse_socket_address("00000000000000000000000001000000:0277"),
+      Some(("::1".to_string(), 631))
+    );
+    // fe80::1 は 32bit ごとにリトルエンディアンで格納される
+    assert_eq!(
+      parse_socket_address("000080FE000000000000000001000000:0016"),
+      Some(("fe80::1".to_string(), 22))
+    );
+  }
+
+  #[test]
+  fn parses_ipv4_mapped_socket_address() {
+    assert_eq!(
+      parse_socket_address("0000000000000000FFFF00000100007F:1F90"),
+      Some(("::ffff:127.0.0.1".to_string(), 8080))
+    );
+  }
+
+  #[test]
+  fn rejects_invalid_socket_address() {
+    assert_eq!(parse_socket_address("0100007F"), None);
+    assert_eq!(parse_socket_address("0100007F:XYZ"), None);
+    assert_eq!(parse_socket_address("01007F:0050"), None);
+    assert_eq!(parse_socket_address("ZZ00007F:0050"), None);
+  }
+
+  #[test]
+  fn parses_socket_link() {
+    assert_eq!(parse_socket_link("socket:[12345]"), Some(12345));
+    assert_eq!(parse_socket_link("pipe:[12345]"), None);
+    assert_eq!(parse_socket_link("/dev/null"), None);
+    assert_eq!(parse_socket_link("socket:[abc]"), None);
+    assert_eq!(parse_socket_link("socket:[12345"), None);
+  }
+}
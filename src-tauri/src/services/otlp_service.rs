@@ -0,0 +1,588 @@
+use crate::enums::metric::MetricKind;
+use crate::services::metric_history_service::{MetricHistory, MetricSample};
+use crate::{log_debug, log_internal, log_warn};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use sysinfo::{Networks, System};
+
+///
+/// 計測元として送信する名前（`service.name` とスコープ名）
+///
+const SERVICE_NAME: &str = "hardware-monitor";
+
+///
+/// OTLP の `AGGREGATION_TEMPORALITY_CUMULATIVE`
+///
+const AGGREGATION_TEMPORALITY_CUMULATIVE: u8 = 2;
+
+///
+/// 設定の変更を確認する間隔
+///
+const SETTINGS_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct OtlpSettings {
+  pub enabled: bool,
+  pub endpoint: String, // OTLP/HTTP のメトリクスの送信先（JSON エンコーディングのみ対応し、Protobuf では送信しない）
+  pub headers: BTreeMap<String, String>, // 認証ヘッダーなど
+  pub export_interval_secs: u64,
+  pub timeout_secs: u64,
+  pub resource_attributes: BTreeMap<String, String>, // 自動で設定する属性に追加・上書きする
+}
+
+impl Default for OtlpSettings {
+  fn default() -> Self {
+    Self {
+      enabled: false,
+      endpoint: "http://localhost:4318/v1/metrics".to_string(),
+      headers: BTreeMap::new(),
+      export_interval_secs: 60,
+      timeout_secs: 10,
+      resource_attributes: BTreeMap::new(),
+    }
+  }
+}
+
+impl OtlpSettings {
+  ///
+  /// ## 設定の内容を検証
+  ///
+  pub fn validate(&self) -> Result<(), String> {
+    let url = reqwest::Url::parse(&self.endpoint)
+      .map_err(|e| format!("Invalid OTLP endpoint: {}", e))?;
+    if !matches!(url.scheme(), "http" | "https") {
+      return Err(format!("Unsupported URL scheme: {}", url.scheme()));
+    }
+
+    for (name, value) in &self.headers {
+      reqwest::header::HeaderName::from_bytes(name.as_bytes())
+        .map_err(|e| format!("Invalid header name {}: {}", name, e))?;
+      reqwest::header::HeaderValue::from_str(value)
+        .map_err(|e| format!("Invalid header value for {}: {}", name, e))?;
+    }
+    if self.export_interval_secs == 0 || self.timeout_secs == 0 {
+      return Err("Intervals must be at least 1 second".to_string());
+    }
+
+    Ok(())
+  }
+}
+
+///
+/// ## ホストとハードウェアを識別するリソース属性
+///
+/// - OpenTelemetry のセマンティック規約の名前を使用する
+/// - `overrides` の値で追加・上書きする
+///
+pub fn resource_attributes(
+  sys: &System,
+  app_version: &str,
+  overrides: &BTreeMap<String, String>,
+) -> BTreeMap<String, String> {
+  let mut attributes = BTreeMap::new();
+
+  attributes.insert("service.name".to_string(), SERVICE_NAME.to_string());
+  attributes.insert("service.version".to_string(), app_version.to_string());
+  attributes.insert("host.arch".to_string(), std::env::consts::ARCH.to_string());
+  attributes.insert("os.type".to_string(), std::env::consts::OS.to_string());
+
+  if let Some(host_name) = System::host_name() {
+    attributes.insert("host.name".to_string(), host_name);
+  }
+  if let Some(host_id) = read_machine_id() {
+    attributes.insert("host.id".to_string(), host_id);
+  }
+  if let Some(os_version) = System::long_os_version() {
+    attributes.insert("os.description".to_string(), os_version);
+  }
+  if let Some(cpu) = sys.cpus().first() {
+    attributes.insert(
+      "host.cpu.model.name".to_string(),
+      cpu.brand().trim().to_string(),
+    );
+    attributes.insert(
+      "host.cpu.vendor.id".to_string(),
+      cpu.vendor_id().to_string(),
+    );
+  }
+
+  attributes.extend(overrides.clone());
+  attributes
+}
+
+///
+/// ## マシン ID を取得（Linux のみ）
+///
+fn read_machine_id() -> Option<String> {
+  ["/etc/machine-id", "/var/lib/dbus/machine-id"]
+    .iter()
+    .find_map(|path| std::fs::read_to_string(path).ok())
+    .map(|id| id.trim().to_string())
+    .filter(|id| !id.is_empty())
+}
+
+///
+/// ネットワークインターフェースごとの累積送受信バイト数
+///
+#[derive(Debug, Clone)]
+pub struct NetworkCounter {
+  pub interface: String,
+  pub received_bytes: u64,
+  pub transmitted_bytes: u64,
+}
+
+///
+/// ## 指標の OTLP での名前・単位と、送信する値への換算
+///
+fn gauge_definition(
+  metric: MetricKind,
+) -> (&'static str, &'static str, &'static str, f64) {
+  match metric {
+    MetricKind::CpuUsage => (
+      "system.cpu.utilization",
+      "1",
+      "CPU utilization averaged over all cores.",
+      0.01,
+    ),
+    MetricKind::MemoryUsage => (
+      "system.memory.utilization",
+      "1",
+      "Fraction of physical memory in use.",
+      0.01,
+    ),
+    MetricKind::GpuUsage => ("hw.gpu.utilization", "1", "GPU utilization.", 0.01),
+    MetricKind::GpuTemperature => {
+      ("hw.gpu.temperature", "Cel", "Highest GPU temperature.", 1.0)
+    }
+  }
+}
+
+fn to_any_value(value: &str) -> Value {
+  json!({ "stringValue": value })
+}
+
+fn to_key_values<'a>(
+  attributes: impl IntoIterator<Item = (&'a str, &'a str)>,
+) -> Vec<Value> {
+  attributes
+    .into_iter()
+    .map(|(key, value)| json!({ "key": key, "value": to_any_value(value) }))
+    .collect()
+}
+
+///
+/// ## OTLP/HTTP の JSON エンコーディングの `ExportMetricsServiceRequest` を作成
+///
+/// - param sample: `&MetricSample` ゲージとして送信する最新の計測値
+/// - param counters: `&[NetworkCounter]` 累積値（カウンター）として送信する値
+/// - param start_time_millis: `i64` カウンターの累積を開始した時刻（UNIX エポックからのミリ秒）
+/// - param now_millis: `i64` カウンターを計測した時刻
+/// - 64bit の整数は JSON エンコーディングの仕様に従い文字列にする
+///
+pub fn build_request(
+  resource: &BTreeMap<String, String>,
+  app_version: &str,
+  sample: Option<&MetricSample>,
+  counters: &[NetworkCounter],
+  start_time_millis: i64,
+  now_millis: i64,
+) -> Value {
+  let mut metrics = Vec::new();
+
+  if let Some(sample) = sample {
+    let time = (sample.timestamp * 1_000_000).to_string();
+
+    // 出力を安定させるため、指標の名前順に並べる
+    let mut values: Vec<_> = sample
+      .values
+      .iter()
+      .filter(|(_, value)| value.is_finite())
+      .collect();
+    values.sort_by_key(|(metric, _)| metric.key());
+
+    for (metric, value) in values {
+      let (name, unit, description, scale) = gauge_definition(*metric);
+      let value = *value as f64 * scale;
+      metrics.push(json!({
+        "name": name,
+        "unit": unit,
+        "description": description,
+        "gauge": {
+          "dataPoints": [{
+            "timeUnixNano": time,
+            "asDouble": value,
+          }]
+        }
+      }));
+    }
+  }
+
+  if !counters.is_empty() {
+    let start_time = (start_time_millis * 1_000_000).to_string();
+    let time = (now_millis * 1_000_000).to_string();
+
+    let data_points: Vec<Value> = counters
+      .iter()
+      .flat_map(|counter| {
+        [
+          ("receive", counter.received_bytes),
+          ("transmit", counter.transmitted_bytes),
+        ]
+        .map(|(direction, bytes)| {
+          json!({
+            "attributes": to_key_values([
+              ("network.interface.name", counter.interface.as_str()),
+              ("network.io.direction", direction),
+            ]),
+            "startTimeUnixNano": start_time,
+            "timeUnixNano": time,
+            "asInt": bytes.to_string(),
+          })
+        })
+      })
+      .collect();
+
+    metrics.push(json!({
+      "name": "system.network.io",
+      "unit": "By",
+      "description": "Bytes transmitted and received by the network interface.",
+      "sum": {
+        "aggregationTemporality": AGGREGATION_TEMPORALITY_CUMULATIVE,
+        "isMonotonic": true,
+        "dataPoints": data_points,
+      }
+    }));
+  }
+
+  json!({
+    "resourceMetrics": [{
+      "resource": {
+        "attributes": to_key_values(
+          resource.iter().map(|(key, value)| (key.as_str(), value.as_str()))
+        ),
+      },
+      "scopeMetrics": [{
+        "scope": { "name": SERVICE_NAME, "version": app_version },
+        "metrics": metrics,
+      }]
+    }]
+  })
+}
+
+///
+/// ## コレクターへ送信
+///
+pub async fn export(
+  client: &reqwest::Client,
+  settings: &OtlpSettings,
+  request: &Value,
+) -> Result<(), String> {
+  let mut builder = client
+    .post(&settings.endpoint)
+    .timeout(Duration::from_secs(settings.timeout_secs))
+    .json(request);
+  for (name, value) in &settings.headers {
+    builder = builder.header(name, value);
+  }
+
+  let response = builder.send().await.map_err(|e| e.to_string())?;
+  if response.status().is_success() {
+    Ok(())
+  } else {
+    let status = response.status();
+    let body = response.text().await.unwrap_or_default();
+    Err(format!("HTTP {}: {}", status, body.trim()))
+  }
+}
+
+///
+/// ## 一定間隔でメトリクスを送信するタスクを開始
+///
+/// - param get_settings: `SETTINGS_POLL_INTERVAL` ごとに最新の設定を取得する（送信間隔などの変更を再起動なしですぐに反映する）
+/// - param metric_history: `Arc<Mutex<MetricHistory>>` ゲージとして最新の計測値を送信する
+/// - param app_version: `String` `service.version` として送信する
+///
+pub fn spawn_exporter<F>(
+  get_settings: F,
+  system: Arc<Mutex<System>>,
+  metric_history: Arc<Mutex<MetricHistory>>,
+  app_version: String,
+) where
+  F: Fn() -> OtlpSettings + Send + 'static,
+{
+  tauri::async_runtime::spawn(async move {
+    let client = reqwest::Client::new();
+    // ネットワークの累積値は OS の起動時からの値
+    let start_time_millis = System::boot_time() as i64 * 1000;
+    let mut last_export = Instant::now();
+    // リソース属性は起動中に変わらないため、追加・上書きする属性が変わった場合のみ作り直す
+    let mut resource: Option<(BTreeMap<String, String>, BTreeMap<String, String>)> = None;
+    let mut networks = Networks::new();
+
+    loop {
+      tokio::time::sleep(SETTINGS_POLL_INTERVAL).await;
+
+      let settings = get_settings();
+      let export_interval = Duration::from_secs(settings.export_interval_secs.max(1));
+      if !settings.enabled || last_export.elapsed() < export_interval {
+        continue;
+      }
+      last_export = Instant::now();
+
+      if resource.as_ref().map_or(false, |(overrides, _)| {
+        overrides != &settings.resource_attributes
+      }) {
+        resource = None;
+      }
+      let (_, resource) = resource.get_or_insert_with(|| {
+        let sys = system.lock().unwrap();
+        let attributes =
+          resource_attributes(&sys, &app_version, &settings.resource_attributes);
+        (settings.resource_attributes.clone(), attributes)
+      });
+
+      let sample = metric_history.lock().unwrap().latest();
+      // インターフェースの追加・削除に追従しつつ、既存のインターフェースの情報は再利用する
+      networks.refresh_list();
+      let counters: Vec<NetworkCounter> = networks
+        .iter()
+        .map(|(interface, data)| NetworkCounter {
+          interface: interface.clone(),
+          received_bytes: data.total_received(),
+          transmitted_bytes: data.total_transmitted(),
+        })
+        .collect();
+
+      let request = build_request(
+        resource,
+        &app_version,
+        sample.as_ref(),
+        &counters,
+        start_time_millis,
+        chrono::Utc::now().timestamp_millis(),
+      );
+
+      match export(&client, &settings, &request).await {
+        Ok(()) => {
+          log_debug!("exported", "otlp_service", None::<&str>);
+        }
+        Err(e) => {
+          log_warn!("export_failed", "otlp_service", Some(e));
+        }
+      }
+    }
+  });
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::collections::HashMap;
+  use tokio::io::{AsyncReadExt, AsyncWriteExt};
+  use tokio::net::TcpListener;
+
+  fn sample(timestamp: i64, values: &[(MetricKind, f32)]) -> MetricSample {
+    MetricSample {
+      timestamp,
+      values: values.iter().copied().collect::<HashMap<_, _>>(),
+    }
+  }
+
+  fn metrics(request: &Value) -> &Vec<Value> {
+    request["resourceMetrics"][0]["scopeMetrics"][0]["metrics"]
+      .as_array()
+      .unwrap()
+  }
+
+  #[test]
+  fn build_request_scales_gauges_and_uses_nanoseconds() {
+    let sample = sample(
+      1_700_000_000_123,
+      &[
+        (MetricKind::GpuTemperature, 70.0),
+        (MetricKind::CpuUsage, 50.0),
+        (MetricKind::GpuUsage, f32::NAN),
+      ],
+    );
+
+    let request = build_request(&BTreeMap::new(), "1.0.0", Some(&sample), &[], 0, 0);
+    let metrics = metrics(&request);
+
+    // 値が有限でない指標は送信せず、指標の名前順に並べる
+    assert_eq!(metrics.len(), 2);
+    assert_eq!(metrics[0]["name"], "system.cpu.utilization");
+    assert_eq!(metrics[0]["unit"], "1");
+    let point = &metrics[0]["gauge"]["dataPoints"][0];
+    assert_eq!(point["asDouble"], 0.5);
+    assert_eq!(point["timeUnixNano"], "1700000000123000000");
+
+    assert_eq!(metrics[1]["name"], "hw.gpu.temperature");
+    assert_eq!(metrics[1]["unit"], "Cel");
+    assert_eq!(metrics[1]["gauge"]["dataPoints"][0]["asDouble"], 70.0);
+  }
+
+  #[test]
+  fn build_request_sends_counters_as_cumulative_sums() {
+    let counters = [NetworkCounter {
+      interface: "eth0".to_string(),
+      received_bytes: u64::MAX,
+      transmitted_bytes: 42,
+    }];
+
+    let request = build_request(
+      &BTreeMap::new(),
+      "1.0.0",
+      None,
+      &counters,
+      1_600_000_000_000,
+      1_700_000_000_000,
+    );
+    let metrics = metrics(&request);
+
+    assert_eq!(metrics.len(), 1);
+    let sum = &metrics[0]["sum"];
+    assert_eq!(metrics[0]["name"], "system.network.io");
+    assert_eq!(sum["aggregationTemporality"], 2);
+    assert_eq!(sum["isMonotonic"], true);
+
+    let points = sum["dataPoints"].as_array().unwrap();
+    assert_eq!(points.len(), 2);
+    // 64bit の整数は精度を失わないよう文字列で送信する
+    assert_eq!(points[0]["asInt"], "18446744073709551615");
+    assert_eq!(points[1]["asInt"], "42");
+    assert_eq!(points[0]["startTimeUnixNano"], "1600000000000000000");
+    assert_eq!(points[0]["timeUnixNano"], "1700000000000000000");
+    assert_eq!(
+      points[1]["attributes"],
+      json!([
+        { "key": "network.interface.name", "value": { "stringValue": "eth0" } },
+        { "key": "network.io.direction", "value": { "stringValue": "transmit" } },
+      ])
+    );
+  }
+
+  #[test]
+  fn resource_attributes_apply_overrides() {
+    let overrides = BTreeMap::from([
+      ("service.name".to_string(), "office-pc".to_string()),
+      ("deployment.environment".to_string(), "lab".to_string()),
+    ]);
+
+    let resource = resource_attributes(&System::new(), "1.2.3", &overrides);
+    let request = build_request(&resource, "1.2.3", None, &[], 0, 0);
+
+    assert_eq!(resource["service.name"], "office-pc");
+    assert_eq!(resource["service.version"], "1.2.3");
+    assert_eq!(resource["deployment.environment"], "lab");
+    assert!(request["resourceMetrics"][0]["resource"]["attributes"]
+      .as_array()
+      .unwrap()
+      .contains(
+        &json!({ "key": "service.name", "value": { "stringValue": "office-pc" } })
+      ));
+    assert_eq!(
+      request["resourceMetrics"][0]["scopeMetrics"][0]["scope"],
+      json!({ "name": SERVICE_NAME, "version": "1.2.3" })
+    );
+  }
+
+  ///
+  /// 1 回だけリクエストを受け付け、指定したステータスコードを返すローカルの HTTP サーバー
+  ///
+  /// - 受信したリクエスト（ヘッダーとボディ）を返す
+  ///
+  async fn stub_server(
+    status: u16,
+    body: &'static str,
+  ) -> (String, tokio::task::JoinHandle<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/v1/metrics", listener.local_addr().unwrap());
+
+    let handle = tokio::spawn(async move {
+      let (mut stream, _) = listener.accept().await.unwrap();
+      let mut request = Vec::new();
+      let mut buffer = [0u8; 4096];
+
+      loop {
+        let read = stream.read(&mut buffer).await.unwrap();
+        if read == 0 {
+          break;
+        }
+        request.extend_from_slice(&buffer[..read]);
+
+        let text = String::from_utf8_lossy(&request).to_string();
+        if let Some(header_end) = text.find("\r\n\r\n") {
+          let content_length = text[..header_end]
+            .lines()
+            .find_map(|line| {
+              let (name, value) = line.split_once(':')?;
+              name
+                .eq_ignore_ascii_case("content-length")
+                .then(|| value.trim().parse::<usize>().ok())?
+            })
+            .unwrap_or(0);
+          if request.len() >= header_end + 4 + content_length {
+            break;
+          }
+        }
+      }
+
+      let response = format!(
+        "HTTP/1.1 {} Stub\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+      );
+      stream.write_all(response.as_bytes()).await.unwrap();
+      stream.shutdown().await.ok();
+
+      String::from_utf8_lossy(&request).to_string()
+    });
+
+    (url, handle)
+  }
+
+  fn settings(endpoint: &str) -> OtlpSettings {
+    OtlpSettings {
+      enabled: true,
+      endpoint: endpoint.to_string(),
+      headers: BTreeMap::from([(
+        "Authorization".to_string(),
+        "Bearer secret".to_string(),
+      )]),
+      ..Default::default()
+    }
+  }
+
+  #[tokio::test]
+  async fn export_sends_headers_and_json_body() {
+    let (url, server) = stub_server(200, "").await;
+    let request = json!({ "resourceMetrics": [] });
+
+    let result = export(&reqwest::Client::new(), &settings(&url), &request).await;
+
+    assert_eq!(result, Ok(()));
+    let received = server.await.unwrap().to_lowercase();
+    assert!(received.starts_with("post /v1/metrics http/1.1\r\n"));
+    assert!(received.contains("\r\nauthorization: bearer secret\r\n"));
+    assert!(received.contains("\r\ncontent-type: application/json\r\n"));
+    assert!(received.ends_with("\r\n\r\n{\"resourcemetrics\":[]}"));
+  }
+
+  #[tokio::test]
+  async fn export_reports_non_success_status() {
+    let (url, server) = stub_server(503, " collector unavailable \n").await;
+
+    let result = export(&reqwest::Client::new(), &settings(&url), &json!({})).await;
+
+    assert_eq!(
+      result,
+      Err("HTTP 503 Service Unavailable: collector unavailable".to_string())
+    );
+    server.await.unwrap();
+  }
+}
@@ -0,0 +1,128 @@
+use crate::enums::process::ProcessGroupBy;
+use crate::services::process_service;
+use crate::utils::formatter;
+use std::collections::HashMap;
+use std::collections::VecDeque;
+use sysinfo::{Pid, Process};
+
+///
+/// グループごとの使用量の履歴
+///
+#[derive(Debug, Default, Clone)]
+pub struct ProcessGroupHistory {
+  pub cpu: VecDeque<f32>,
+  pub memory: VecDeque<f32>,
+  pub disk_read: VecDeque<f32>,
+  pub disk_write: VecDeque<f32>,
+}
+
+///
+/// 1回のサンプリングにおけるグループの合計値
+///
+#[derive(Debug, Default, Clone)]
+pub struct ProcessGroupSample {
+  pub name: String,
+  pub pids: Vec<Pid>,
+  pub cpu_usage: f32,
+  pub memory_usage: f32,
+  pub disk_read_bytes_per_sec: f32,
+  pub disk_write_bytes_per_sec: f32,
+}
+
+///
+/// ## プロセスが属するグループのキーと表示名を取得
+///
+/// - `ExecutablePath`: 実行ファイルのパス（取得できない場合はプロセス名）
+/// - `Name`: プロセス名
+/// - `TreeRoot`: init 直下の祖先プロセス（PID と開始時刻で識別）
+///
+pub fn group_key(
+  process: &Process,
+  processes: &HashMap<Pid, Process>,
+  group_by: ProcessGroupBy,
+) -> (String, String) {
+  let name = process.name().to_string_lossy().into_owned();
+
+  match group_by {
+    ProcessGroupBy::Name => (name.clone(), name),
+    ProcessGroupBy::ExecutablePath => match process.exe() {
+      Some(exe) => (exe.display().to_string(), name),
+      None => (name.clone(), name),
+    },
+    ProcessGroupBy::TreeRoot => {
+      let root = find_tree_root(process, processes);
+      (
+        format!("{}:{}", root.pid().as_u32(), root.start_time()),
+        root.name().to_string_lossy().into_owned(),
+      )
+    }
+  }
+}
+
+///
+/// ## ツリーのルートとなる祖先プロセスを探す
+///
+/// - 親を持たないプロセス（init など）の直下で止めることで、アプリケーション単位にまとめる
+///
+fn find_tree_root<'a>(
+  process: &'a Process,
+  processes: &'a HashMap<Pid, Process>,
+) -> &'a Process {
+  let mut current = process;
+
+  // 循環していても無限ループしないよう、辿る回数をプロセス数で制限する
+  for _ in 0..processes.len() {
+    let parent = match current.parent().and_then(|ppid| processes.get(&ppid)) {
+      Some(parent) if parent.pid() != current.pid() => parent,
+      _ => break,
+    };
+
+    let is_top_level = match parent.parent() {
+      Some(grandparent) => !processes.contains_key(&grandparent),
+      None => true,
+    };
+    if is_top_level {
+      break;
+    }
+
+    current = parent;
+  }
+
+  current
+}
+
+///
+/// ## プロセスをグループごとに集計
+///
+/// - param rates: `impl Fn(Pid) -> (f32, f32)` プロセスごとのディスク読み書き速度（bytes/s）
+/// - スレッドのエントリは集計しない（インスタンス数とメモリを二重に数えないため）
+///
+pub fn aggregate(
+  processes: &HashMap<Pid, Process>,
+  group_by: ProcessGroupBy,
+  rates: impl Fn(Pid) -> (f32, f32),
+) -> HashMap<String, ProcessGroupSample> {
+  let mut groups: HashMap<String, ProcessGroupSample> = HashMap::new();
+
+  for (pid, process) in processes {
+    if process_service::is_thread(process) {
+      continue;
+    }
+
+    let (key, name) = group_key(process, processes, group_by);
+    let (read_rate, write_rate) = rates(*pid);
+
+    let group = groups.entry(key).or_insert_with(|| ProcessGroupSample {
+      name,
+      ..Default::default()
+    });
+
+    group.pids.push(*pid);
+    group.cpu_usage += process.cpu_usage();
+    group.memory_usage += formatter::bytes_to_mb(process.memory());
+    group.disk_read_bytes_per_sec += read_rate;
+    group.disk_write_bytes_per_sec += write_rate;
+  }
+
+  groups
+}
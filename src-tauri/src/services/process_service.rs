@@ -0,0 +1,1008 @@
+use crate::services::procfs_service;
+use crate::utils::file::get_app_data_dir;
+use crate::{log_debug, log_error, log_info, log_internal, log_warn};
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::fs::OpenOptions;
+use std::io::Write;
+use sysinfo::{Pid, Process, ProcessRefreshKind, ProcessesToUpdate, System, Users};
+
+const AUDIT_LOG_FILENAME: &str = "process_audit.log";
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ProcessAction {
+  Terminate,
+  Kill,
+  Suspend,
+  Resume,
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ProcessActionStatus {
+  Success,
+  NotFound,
+  PidReused,
+  PermissionDenied,
+  Unsupported,
+  Failed,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessActionResult {
+  pub pid: u32,
+  pub start_time: u64,
+  pub name: Option<String>,
+  pub action: ProcessAction,
+  pub status: ProcessActionStatus,
+  pub message: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AuditLogEntry<'a> {
+  timestamp: String,
+  #[serde(flatten)]
+  result: &'a ProcessActionResult,
+}
+
+///
+/// ## プロセスに対して操作（終了・強制終了・一時停止・再開）を実行
+///
+/// - param system: `&mut System` システム情報
+/// - param pid: `u32` 対象のプロセスID
+/// - param start_time: `u64` 対象プロセスの開始時刻（PID 再利用の検出に使用）
+/// - param action: `ProcessAction` 実行する操作
+///
+/// - 実行結果は成否に関わらず監査ログに記録する
+///
+pub fn execute_action(
+  system: &mut System,
+  pid: u32,
+  start_time: u64,
+  action: ProcessAction,
+) -> ProcessActionResult {
+  let sys_pid = Pid::from_u32(pid);
+  system.refresh_processes(ProcessesToUpdate::Some(&[sys_pid]), true);
+
+  let result = match system.process(sys_pid) {
+    None => ProcessActionResult {
+      pid,
+      start_time,
+      name: None,
+      action,
+      status: ProcessActionStatus::NotFound,
+      message: Some("Process not found".to_string()),
+    },
+    Some(process) if process.start_time() != start_time => ProcessActionResult {
+      pid,
+      start_time,
+      name: Some(process.name().to_string_lossy().into_owned()),
+      action,
+      status: ProcessActionStatus::PidReused,
+      message: Some(format!(
+        "PID {} now belongs to a process started at {}",
+        pid,
+        process.start_time()
+      )),
+    },
+    Some(process) => {
+      let (status, message) = send_signal(process, action);
+
+      ProcessActionResult {
+        pid,
+        start_time,
+        name: Some(process.name().to_string_lossy().into_owned()),
+        action,
+        status,
+        message,
+      }
+    }
+  };
+
+  write_audit_log(&result);
+
+  result
+}
+
+///
+/// ## 操作に対応するシグナルを送信
+///
+/// - sysinfo の `kill_with` は失敗時の errno を保証しないため、`kill(2)` を直接呼び出してすぐに errno を読む
+///
+#[cfg(target_os = "linux")]
+fn send_signal(
+  process: &Process,
+  action: ProcessAction,
+) -> (ProcessActionStatus, Option<String>) {
+  let signal = match action {
+    ProcessAction::Terminate => libc::SIGTERM,
+    ProcessAction::Kill => libc::SIGKILL,
+    ProcessAction::Suspend => libc::SIGSTOP,
+    ProcessAction::Resume => libc::SIGCONT,
+  };
+
+  match unsafe { libc::kill(process.pid().as_u32() as libc::pid_t, signal) } {
+    0 => (ProcessActionStatus::Success, None),
+    _ => classify_failure(std::io::Error::last_os_error()),
+  }
+}
+
+///
+/// ## シグナル送信の失敗理由を判別
+///
+#[cfg(target_os = "linux")]
+fn classify_failure(err: std::io::Error) -> (ProcessActionStatus, Option<String>) {
+  match err.raw_os_error() {
+    Some(libc::EPERM) => (
+      ProcessActionStatus::PermissionDenied,
+      Some("Permission denied: elevated privileges are required".to_string()),
+    ),
+    // 確認してから送信するまでの間に終了した
+    Some(libc::ESRCH) => (
+      ProcessActionStatus::NotFound,
+      Some("Process not found".to_string()),
+    ),
+    _ => (ProcessActionStatus::Failed, Some(err.to_string())),
+  }
+}
+
+///
+/// ## 操作に対応するシグナルを送信
+///
+/// - 終了（`Terminate`）をサポートしない環境（Windows）では、強制終了で代替せず `Unsupported` を返す
+///
+#[cfg(not(target_os = "linux"))]
+fn send_signal(
+  process: &Process,
+  action: ProcessAction,
+) -> (ProcessActionStatus, Option<String>) {
+  use sysinfo::Signal;
+
+  let signal = match action {
+    ProcessAction::Terminate => Signal::Term,
+    ProcessAction::Kill => Signal::Kill,
+    ProcessAction::Suspend => Signal::Stop,
+    ProcessAction::Resume => Signal::Continue,
+  };
+
+  match process.kill_with(signal) {
+    Some(true) => (ProcessActionStatus::Success, None),
+    Some(false) => (
+      ProcessActionStatus::Failed,
+      Some("Failed to signal process (administrator rights may be required)".to_string()),
+    ),
+    None if action == ProcessAction::Terminate => (
+      ProcessActionStatus::Unsupported,
+      Some(
+        "Graceful termination is not supported on this platform; use Kill instead"
+          .to_string(),
+      ),
+    ),
+    None => (
+      ProcessActionStatus::Unsupported,
+      Some(format!("{:?} is not supported on this platform", action)),
+    ),
+  }
+}
+
+///
+/// ## プロセス操作の監査ログを書き込む
+///
+/// - `process_audit.log` に JSON Lines 形式で追記する
+///
+fn write_audit_log(result: &ProcessActionResult) {
+  let entry = AuditLogEntry {
+    timestamp: Local::now().to_rfc3339(),
+    result,
+  };
+
+  let serialized = match serde_json::to_string(&entry) {
+    Ok(serialized) => serialized,
+    Err(e) => {
+      log_error!("serialize_failed", "write_audit_log", Some(e.to_string()));
+      return;
+    }
+  };
+
+  log_info!(&serialized, "write_audit_log", None::<&str>);
+
+  let audit_file = get_app_data_dir(AUDIT_LOG_FILENAME);
+  if let Some(parent) = audit_file.parent() {
+    if let Err(e) = std::fs::create_dir_all(parent) {
+      log_error!("create_dir_failed", "write_audit_log", Some(e.to_string()));
+      return;
+    }
+  }
+
+  if let Err(e) = OpenOptions::new()
+    .create(true)
+    .append(true)
+    .open(audit_file)
+    .and_then(|mut file| writeln!(file, "{}", serialized))
+  {
+    log_warn!("write_failed", "write_audit_log", Some(e.to_string()));
+  }
+
+  log_debug!("end", "write_audit_log", None::<&str>);
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum PriorityClass {
+  Idle,
+  BelowNormal,
+  Normal,
+  AboveNormal,
+  High,
+  Realtime,
+}
+
+impl PriorityClass {
+  ///
+  /// ## 優先度クラスに対応する nice 値
+  ///
+  pub fn to_nice(self) -> i32 {
+    match self {
+      PriorityClass::Idle => 19,
+      PriorityClass::BelowNormal => 10,
+      PriorityClass::Normal => 0,
+      PriorityClass::AboveNormal => -5,
+      PriorityClass::High => -10,
+      PriorityClass::Realtime => -20,
+    }
+  }
+
+  ///
+  /// ## nice 値に最も近い優先度クラス
+  ///
+  pub fn from_nice(nice: i32) -> Self {
+    match nice {
+      n if n >= 15 => PriorityClass::Idle,
+      n if n >= 5 => PriorityClass::BelowNormal,
+      n if n > -5 => PriorityClass::Normal,
+      n if n > -10 => PriorityClass::AboveNormal,
+      n if n > -20 => PriorityClass::High,
+      _ => PriorityClass::Realtime,
+    }
+  }
+}
+
+#[derive(Debug, Serialize, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessPriority {
+  pub class: PriorityClass,
+  pub nice: Option<i32>, // Windows では None
+}
+
+///
+/// ## 対象プロセスが存在するか確認
+///
+fn ensure_process_exists(system: &mut System, pid: Pid) -> Result<(), String> {
+  system.refresh_processes(ProcessesToUpdate::Some(&[pid]), true);
+
+  match system.process(pid) {
+    Some(_) => Ok(()),
+    None => Err(format!("Process not found: {}", pid)),
+  }
+}
+
+///
+/// ## 権限不足のエラーメッセージ
+///
+fn permission_error(pid: Pid, operation: &str) -> String {
+  format!(
+    "Permission denied: elevated privileges are required to {} of process {}",
+    operation, pid
+  )
+}
+
+///
+/// ## プロセスの優先度を取得
+///
+pub fn get_priority(system: &mut System, pid: Pid) -> Result<ProcessPriority, String> {
+  ensure_process_exists(system, pid)?;
+  platform::get_priority(pid)
+}
+
+///
+/// ## プロセスの優先度を変更
+///
+/// - param class: `Option<PriorityClass>` 優先度クラス
+/// - param nice: `Option<i32>` nice 値（-20 ~ 19）。`class` より優先される
+///
+/// - Linux ではプロセスのすべてのスレッドに適用する
+///
+pub fn set_priority(
+  system: &mut System,
+  pid: Pid,
+  class: Option<PriorityClass>,
+  nice: Option<i32>,
+) -> Result<ProcessPriority, String> {
+  ensure_process_exists(system, pid)?;
+
+  let nice = match (nice, class) {
+    (Some(nice), _) if !(-20..=19).contains(&nice) => {
+      return Err(format!("Invalid nice value: {}", nice));
+    }
+    (Some(nice), _) => nice,
+    (None, Some(class)) => class.to_nice(),
+    (None, None) => return Err("Either class or nice must be specified".to_string()),
+  };
+
+  log_info!(
+    &format!("pid: {}, nice: {}", pid, nice),
+    "set_priority",
+    None::<&str>
+  );
+
+  platform::set_priority(pid, nice)?;
+  platform::get_priority(pid)
+}
+
+///
+/// ## 優先度と CPU アフィニティを取得（プロセスの存在は確認しない）
+///
+/// - 一覧や詳細の表示用。権限不足などで取得できない値は `None`
+///
+pub fn read_scheduling(pid: Pid) -> (Option<ProcessPriority>, Option<Vec<usize>>) {
+  (
+    platform::get_priority(pid).ok(),
+    platform::get_affinity(pid).ok(),
+  )
+}
+
+///
+/// ## プロセスの CPU アフィニティ（実行可能な論理コアの番号）を取得
+///
+pub fn get_affinity(system: &mut System, pid: Pid) -> Result<Vec<usize>, String> {
+  ensure_process_exists(system, pid)?;
+  platform::get_affinity(pid)
+}
+
+///
+/// ## プロセスの CPU アフィニティを変更
+///
+/// - param cpus: `Vec<usize>` 実行を許可する論理コアの番号
+///
+/// - Linux ではプロセスのすべてのスレッドに適用する
+///
+pub fn set_affinity(
+  system: &mut System,
+  pid: Pid,
+  cpus: Vec<usize>,
+) -> Result<Vec<usize>, String> {
+  ensure_process_exists(system, pid)?;
+
+  let cpu_count = system.cpus().len();
+  if cpus.is_empty() {
+    return Err("At least one CPU must be specified".to_string());
+  }
+  if let Some(cpu) = cpus.iter().find(|&&cpu| cpu >= cpu_count) {
+    return Err(format!("Invalid CPU index: {}", cpu));
+  }
+
+  log_info!(
+    &format!("pid: {}, cpus: {:?}", pid, cpus),
+    "set_affinity",
+    None::<&str>
+  );
+
+  platform::set_affinity(pid, &cpus)?;
+  platform::get_affinity(pid)
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+  use super::{permission_error, PriorityClass, ProcessPriority};
+  use crate::services::procfs_service;
+  use std::io::{Error, ErrorKind};
+  use sysinfo::Pid;
+
+  fn map_os_error(err: Error, pid: Pid, operation: &str) -> String {
+    match err.kind() {
+      ErrorKind::PermissionDenied => permission_error(pid, operation),
+      _ => format!("Failed to {} of process {}: {}", operation, pid, err),
+    }
+  }
+
+  ///
+  /// ## プロセスのすべてのスレッドに対して処理を実行
+  ///
+  /// - `setpriority` と `sched_setaffinity` はスレッド単位で作用するため、`/proc/<pid>/task` のすべてのスレッドに適用する
+  /// - 途中で終了したスレッドは無視し、それ以外の失敗はスレッド ID とともにまとめて返す
+  ///
+  fn for_each_thread(
+    pid: Pid,
+    operation: &str,
+    f: impl Fn(u32) -> Result<(), Error>,
+  ) -> Result<(), String> {
+    let tids = procfs_service::list_thread_ids(pid.as_u32())
+      .unwrap_or_else(|_| vec![pid.as_u32()]);
+
+    let mut failures = Vec::new();
+    for tid in tids {
+      match f(tid) {
+        Ok(()) => {}
+        Err(e) if e.raw_os_error() == Some(libc::ESRCH) && tid != pid.as_u32() => {}
+        Err(e) => failures.push((tid, e)),
+      }
+    }
+
+    if failures.is_empty() {
+      return Ok(());
+    }
+    // プロセス自体（メインスレッド）のみ失敗した場合は、そのエラーをそのまま返す
+    if failures.len() == 1 && failures[0].0 == pid.as_u32() {
+      let (_, err) = failures.remove(0);
+      return Err(map_os_error(err, pid, operation));
+    }
+    if failures
+      .iter()
+      .all(|(_, err)| err.kind() == ErrorKind::PermissionDenied)
+    {
+      return Err(permission_error(pid, operation));
+    }
+
+    Err(format!(
+      "Failed to {} of process {} for threads: {}",
+      operation,
+      pid,
+      failures
+        .iter()
+        .map(|(tid, err)| format!("{} ({})", tid, err))
+        .collect::<Vec<_>>()
+        .join(", ")
+    ))
+  }
+
+  pub fn get_priority(pid: Pid) -> Result<ProcessPriority, String> {
+    let stat = procfs_service::read_stat(pid.as_u32())?;
+
+    Ok(ProcessPriority {
+      class: PriorityClass::from_nice(stat.nice),
+      nice: Some(stat.nice),
+    })
+  }
+
+  pub fn set_priority(pid: Pid, nice: i32) -> Result<(), String> {
+    for_each_thread(pid, "change priority", |tid| {
+      match unsafe { libc::setpriority(libc::PRIO_PROCESS, tid as libc::id_t, nice) } {
+        0 => Ok(()),
+        _ => Err(Error::last_os_error()),
+      }
+    })
+  }
+
+  pub fn get_affinity(pid: Pid) -> Result<Vec<usize>, String> {
+    let mut set: libc::cpu_set_t = unsafe { std::mem::zeroed() };
+
+    let result = unsafe {
+      libc::sched_getaffinity(
+        pid.as_u32() as libc::pid_t,
+        std::mem::size_of::<libc::cpu_set_t>(),
+        &mut set,
+      )
+    };
+
+    if result != 0 {
+      return Err(map_os_error(Error::last_os_error(), pid, "read affinity"));
+    }
+
+    Ok(
+      (0..libc::CPU_SETSIZE as usize)
+        .filter(|&cpu| unsafe { libc::CPU_ISSET(cpu, &set) })
+        .collect(),
+    )
+  }
+
+  pub fn set_affinity(pid: Pid, cpus: &[usize]) -> Result<(), String> {
+    let mut set: libc::cpu_set_t = unsafe { std::mem::zeroed() };
+
+    for &cpu in cpus {
+      unsafe { libc::CPU_SET(cpu, &mut set) };
+    }
+
+    for_each_thread(pid, "change affinity", |tid| {
+      let result = unsafe {
+        libc::sched_setaffinity(
+          tid as libc::pid_t,
+          std::mem::size_of::<libc::cpu_set_t>(),
+          &set,
+        )
+      };
+
+      match result {
+        0 => Ok(()),
+        _ => Err(Error::last_os_error()),
+      }
+    })
+  }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+  use super::{permission_error, PriorityClass, ProcessPriority};
+  use sysinfo::Pid;
+  use windows::Win32::Foundation::{CloseHandle, E_ACCESSDENIED, HANDLE};
+  use windows::Win32::System::Threading::{
+    GetPriorityClass, GetProcessAffinityMask, OpenProcess, SetPriorityClass,
+    SetProcessAffinityMask, ABOVE_NORMAL_PRIORITY_CLASS, BELOW_NORMAL_PRIORITY_CLASS,
+    HIGH_PRIORITY_CLASS, IDLE_PRIORITY_CLASS, NORMAL_PRIORITY_CLASS,
+    PROCESS_ACCESS_RIGHTS, PROCESS_QUERY_LIMITED_INFORMATION, PROCESS_SET_INFORMATION,
+    REALTIME_PRIORITY_CLASS,
+  };
+
+  fn map_os_error(err: windows::core::Error, pid: Pid, operation: &str) -> String {
+    if err.code() == E_ACCESSDENIED {
+      permission_error(pid, operation)
+    } else {
+      format!("Failed to {} of process {}: {}", operation, pid, err)
+    }
+  }
+
+  ///
+  /// ## プロセスハンドルを開いて処理を実行し、必ずハンドルを閉じる
+  ///
+  fn with_process<T>(
+    pid: Pid,
+    access: PROCESS_ACCESS_RIGHTS,
+    operation: &str,
+    f: impl FnOnce(HANDLE) -> Result<T, String>,
+  ) -> Result<T, String> {
+    let handle = unsafe { OpenProcess(access, false, pid.as_u32()) }
+      .map_err(|e| map_os_error(e, pid, operation))?;
+
+    let result = f(handle);
+
+    unsafe {
+      let _ = CloseHandle(handle);
+    }
+
+    result
+  }
+
+  pub fn get_priority(pid: Pid) -> Result<ProcessPriority, String> {
+    with_process(
+      pid,
+      PROCESS_QUERY_LIMITED_INFORMATION,
+      "read priority",
+      |handle| {
+        let class = match unsafe { GetPriorityClass(handle) } {
+          0 => {
+            return Err(map_os_error(
+              windows::core::Error::from_win32(),
+              pid,
+              "read priority",
+            ))
+          }
+          c if c == IDLE_PRIORITY_CLASS.0 => PriorityClass::Idle,
+          c if c == BELOW_NORMAL_PRIORITY_CLASS.0 => PriorityClass::BelowNormal,
+          c if c == ABOVE_NORMAL_PRIORITY_CLASS.0 => PriorityClass::AboveNormal,
+          c if c == HIGH_PRIORITY_CLASS.0 => PriorityClass::High,
+          c if c == REALTIME_PRIORITY_CLASS.0 => PriorityClass::Realtime,
+          _ => PriorityClass::Normal,
+        };
+
+        Ok(ProcessPriority { class, nice: None })
+      },
+    )
+  }
+
+  pub fn set_priority(pid: Pid, nice: i32) -> Result<(), String> {
+    let class = match PriorityClass::from_nice(nice) {
+      PriorityClass::Idle => IDLE_PRIORITY_CLASS,
+      PriorityClass::BelowNormal => BELOW_NORMAL_PRIORITY_CLASS,
+      PriorityClass::Normal => NORMAL_PRIORITY_CLASS,
+      PriorityClass::AboveNormal => ABOVE_NORMAL_PRIORITY_CLASS,
+      PriorityClass::High => HIGH_PRIORITY_CLASS,
+      PriorityClass::Realtime => REALTIME_PRIORITY_CLASS,
+    };
+
+    with_process(pid, PROCESS_SET_INFORMATION, "change priority", |handle| {
+      unsafe { SetPriorityClass(handle, class) }
+        .map_err(|e| map_os_error(e, pid, "change priority"))
+    })
+  }
+
+  pub fn get_affinity(pid: Pid) -> Result<Vec<usize>, String> {
+    with_process(
+      pid,
+      PROCESS_QUERY_LIMITED_INFORMATION,
+      "read affinity",
+      |handle| {
+        let mut process_mask: usize = 0;
+        let mut system_mask: usize = 0;
+
+        unsafe { GetProcessAffinityMask(handle, &mut process_mask, &mut system_mask) }
+          .map_err(|e| map_os_error(e, pid, "read affinity"))?;
+
+        Ok(
+          (0..usize::BITS as usize)
+            .filter(|&cpu| process_mask & (1 << cpu) != 0)
+            .collect(),
+        )
+      },
+    )
+  }
+
+  pub fn set_affinity(pid: Pid, cpus: &[usize]) -> Result<(), String> {
+    if cpus.iter().any(|&cpu| cpu >= usize::BITS as usize) {
+      return Err("CPU index exceeds the processor group size".to_string());
+    }
+
+    let mask = cpus.iter().fold(0usize, |mask, &cpu| mask | (1 << cpu));
+
+    with_process(pid, PROCESS_SET_INFORMATION, "change affinity", |handle| {
+      unsafe { SetProcessAffinityMask(handle, mask) }
+        .map_err(|e| map_os_error(e, pid, "change affinity"))
+    })
+  }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows")))]
+mod platform {
+  use super::ProcessPriority;
+  use sysinfo::Pid;
+
+  const UNSUPPORTED: &str = "Not supported on this platform";
+
+  pub fn get_priority(_pid: Pid) -> Result<ProcessPriority, String> {
+    Err(UNSUPPORTED.to_string())
+  }
+
+  pub fn set_priority(_pid: Pid, _nice: i32) -> Result<(), String> {
+    Err(UNSUPPORTED.to_string())
+  }
+
+  pub fn get_affinity(_pid: Pid) -> Result<Vec<usize>, String> {
+    Err(UNSUPPORTED.to_string())
+  }
+
+  pub fn set_affinity(_pid: Pid, _cpus: &[usize]) -> Result<(), String> {
+    Err(UNSUPPORTED.to_string())
+  }
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessDetails {
+  pub pid: u32,
+  pub parent_pid: Option<u32>,
+  pub name: String,
+  pub cmd: Vec<String>,
+  pub exe: Option<String>,
+  pub cwd: Option<String>,
+  pub user_id: Option<String>,
+  pub user_name: Option<String>,
+  pub group_id: Option<String>,
+  pub start_time: u64,
+  pub run_time: u64,
+  pub status: String,
+  pub thread_count: Option<u64>,
+  pub cpu_time_secs: Option<f64>,
+  pub open_fd_count: Option<usize>,
+  pub environment_count: usize,
+  pub environment_bytes: usize,
+  pub memory: ProcessMemoryBreakdown,
+  pub priority: Option<ProcessPriority>, // 権限不足などで取得できない場合は None
+  pub cpu_affinity: Option<Vec<usize>>,
+}
+
+///
+/// ## プロセスの詳細情報を取得
+///
+/// - `sysinfo::Process` の情報に加え、`/proc/<pid>` からスレッド数・累積CPU時間・FD数を取得
+///
+pub fn get_process_details(
+  system: &mut System,
+  pid: u32,
+) -> Result<ProcessDetails, String> {
+  let sys_pid = Pid::from_u32(pid);
+
+  // CPU 使用率はサンプラーの計測間隔を乱さないよう更新しない
+  system.refresh_processes_specifics(
+    ProcessesToUpdate::Some(&[sys_pid]),
+    true,
+    ProcessRefreshKind::everything().without_cpu(),
+  );
+
+  let process = system
+    .process(sys_pid)
+    .ok_or_else(|| format!("Process not found: {}", pid))?;
+
+  let users = Users::new_with_refreshed_list();
+  let user_name = process
+    .user_id()
+    .and_then(|uid| users.get_user_by_id(uid))
+    .map(|user| user.name().to_string());
+
+  let stat = procfs_service::read_stat(pid).ok();
+  let cpu_time_secs = stat.as_ref().map(|stat| {
+    (stat.utime + stat.stime) as f64 / procfs_service::clock_ticks_per_second() as f64
+  });
+
+  let environ = process.environ();
+
+  let mut memory = ProcessMemoryBreakdown::from_process(process);
+  fill_memory_breakdown(pid, &mut memory);
+
+  let (priority, cpu_affinity) = read_scheduling(sys_pid);
+
+  Ok(ProcessDetails {
+    pid,
+    parent_pid: process.parent().map(|ppid| ppid.as_u32()),
+    name: process.name().to_string_lossy().into_owned(),
+    cmd: process
+      .cmd()
+      .iter()
+      .map(|arg| arg.to_string_lossy().into_owned())
+      .collect(),
+    exe: process.exe().map(|path| path.display().to_string()),
+    cwd: process.cwd().map(|path| path.display().to_string()),
+    user_id: process.user_id().map(|uid| (**uid).to_string()),
+    user_name,
+    group_id: process.group_id().map(|gid| (*gid).to_string()),
+    start_time: process.start_time(),
+    run_time: process.run_time(),
+    status: process.status().to_string(),
+    thread_count: stat
+      .as_ref()
+      .map(|stat| stat.num_threads)
+      .or_else(|| process.tasks().map(|tasks| tasks.len() as u64)),
+    cpu_time_secs,
+    open_fd_count: procfs_service::count_open_fds(pid),
+    environment_count: environ.len(),
+    environment_bytes: environ.iter().map(|var| var.len()).sum(),
+    memory,
+    priority,
+    cpu_affinity,
+  })
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessMemoryBreakdown {
+  pub rss: Option<u64>,
+  pub virtual_size: Option<u64>,
+  pub shared: Option<u64>,
+  pub private: Option<u64>,
+  pub swap: Option<u64>,
+  pub pss: Option<u64>,
+}
+
+impl ProcessMemoryBreakdown {
+  ///
+  /// ## `sysinfo` から取得できる値のみで内訳を作成（単位は bytes）
+  ///
+  pub fn from_process(process: &Process) -> Self {
+    Self {
+      rss: Some(process.memory()),
+      virtual_size: Some(process.virtual_memory()),
+      ..Default::default()
+    }
+  }
+}
+
+///
+/// ## `/proc/<pid>` からメモリ使用量の内訳を補完
+///
+/// - 共有 / プライベートは `smaps_rollup` の値を優先し、読めない場合は `status` の値から求める
+///
+pub fn fill_memory_breakdown(pid: u32, breakdown: &mut ProcessMemoryBreakdown) {
+  let memory = match procfs_service::read_memory(pid) {
+    Some(memory) => memory,
+    None => return, // procfs がない環境では sysinfo の値のみ
+  };
+
+  let sum = |a: Option<u64>, b: Option<u64>| match (a, b) {
+    (None, None) => None,
+    (a, b) => Some(a.unwrap_or(0) + b.unwrap_or(0)),
+  };
+
+  breakdown.rss = memory.rss.or(breakdown.rss);
+  breakdown.virtual_size = memory.virtual_size.or(breakdown.virtual_size);
+  breakdown.shared = sum(memory.shared_clean, memory.shared_dirty)
+    .or_else(|| sum(memory.rss_file, memory.rss_shmem));
+  breakdown.private = sum(memory.private_clean, memory.private_dirty).or(memory.rss_anon);
+  breakdown.swap = memory.swap;
+  breakdown.pss = memory.pss;
+}
+
+///
+/// ## スレッドのエントリかどうか
+///
+/// - Linux の sysinfo は `/proc/<pid>/task` のスレッドもプロセスとして列挙する
+///   （親は所属するプロセス、メモリはプロセス全体の値）
+/// - 集計で二重に数えないよう、プロセス単位の処理では除外する
+/// - カーネルスレッドも `true` になる（htop の既定の表示と同様に除外する）
+///
+pub fn is_thread(process: &Process) -> bool {
+  process.thread_kind().is_some()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  ///
+  /// 操作の対象にする子プロセス（テストが失敗しても終了させる）
+  ///
+  #[cfg(target_os = "linux")]
+  struct SleepChild {
+    child: std::process::Child,
+    system: System,
+  }
+
+  #[cfg(target_os = "linux")]
+  impl SleepChild {
+    fn spawn() -> Self {
+      let child = std::process::Command::new("sleep")
+        .arg("30")
+        .spawn()
+        .unwrap();
+
+      let mut system = System::new();
+      system
+        .refresh_processes(ProcessesToUpdate::Some(&[Pid::from_u32(child.id())]), true);
+
+      Self { child, system }
+    }
+
+    fn process(&self) -> &Process {
+      self.system.process(Pid::from_u32(self.child.id())).unwrap()
+    }
+
+    ///
+    /// ## プロセスの状態が条件を満たすまで待つ（シグナルは非同期に処理される）
+    ///
+    fn wait_for_state(&self, predicate: impl Fn(char) -> bool) -> char {
+      let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+      loop {
+        let state = procfs_service::read_stat(self.child.id()).unwrap().state;
+        if predicate(state) || std::time::Instant::now() > deadline {
+          return state;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(10));
+      }
+    }
+
+    fn wait_signal(&mut self) -> Option<i32> {
+      use std::os::unix::process::ExitStatusExt;
+      self.child.wait().unwrap().signal()
+    }
+  }
+
+  #[cfg(target_os = "linux")]
+  impl Drop for SleepChild {
+    fn drop(&mut self) {
+      let _ = self.child.kill();
+      let _ = self.child.wait();
+    }
+  }
+
+  #[cfg(target_os = "linux")]
+  #[test]
+  fn suspend_and_resume_change_process_state() {
+    let mut sleep = SleepChild::spawn();
+
+    assert_eq!(
+      send_signal(sleep.process(), ProcessAction::Suspend),
+      (ProcessActionStatus::Success, None)
+    );
+    assert_eq!(sleep.wait_for_state(|state| state == 'T'), 'T');
+
+    assert_eq!(
+      send_signal(sleep.process(), ProcessAction::Resume),
+      (ProcessActionStatus::Success, None)
+    );
+    assert_ne!(sleep.wait_for_state(|state| state != 'T'), 'T');
+
+    assert_eq!(
+      send_signal(sleep.process(), ProcessAction::Kill),
+      (ProcessActionStatus::Success, None)
+    );
+    assert_eq!(sleep.wait_signal(), Some(libc::SIGKILL));
+  }
+
+  #[cfg(target_os = "linux")]
+  #[test]
+  fn terminate_sends_sigterm() {
+    let mut sleep = SleepChild::spawn();
+
+    assert_eq!(
+      send_signal(sleep.process(), ProcessAction::Terminate),
+      (ProcessActionStatus::Success, None)
+    );
+    assert_eq!(sleep.wait_signal(), Some(libc::SIGTERM));
+  }
+
+  #[cfg(target_os = "linux")]
+  #[test]
+  fn signal_to_exited_process_is_not_found() {
+    let mut sleep = SleepChild::spawn();
+    sleep.child.kill().unwrap();
+    sleep.child.wait().unwrap();
+
+    assert_eq!(
+      send_signal(sleep.process(), ProcessAction::Terminate).0,
+      ProcessActionStatus::NotFound
+    );
+  }
+
+  #[cfg(target_os = "linux")]
+  #[test]
+  fn classify_failure_maps_errno() {
+    let classify = |errno| classify_failure(std::io::Error::from_raw_os_error(errno)).0;
+
+    assert_eq!(classify(libc::EPERM), ProcessActionStatus::PermissionDenied);
+    assert_eq!(classify(libc::ESRCH), ProcessActionStatus::NotFound);
+    assert_eq!(classify(libc::EINVAL), ProcessActionStatus::Failed);
+  }
+
+  #[cfg(target_os = "linux")]
+  fn current_tid() -> u32 {
+    unsafe { libc::syscall(libc::SYS_gettid) as u32 }
+  }
+
+  ///
+  /// ## 待機するスレッドを起動し、そのスレッド ID を返す
+  ///
+  #[cfg(target_os = "linux")]
+  fn spawn_waiting_thread() -> (
+    u32,
+    std::sync::mpsc::Sender<()>,
+    std::thread::JoinHandle<()>,
+  ) {
+    let (tid_sender, tid_receiver) = std::sync::mpsc::channel();
+    let (stop_sender, stop_receiver) = std::sync::mpsc::channel::<()>();
+
+    let handle = std::thread::spawn(move || {
+      tid_sender.send(current_tid()).unwrap();
+      let _ = stop_receiver.recv();
+    });
+
+    (tid_receiver.recv().unwrap(), stop_sender, handle)
+  }
+
+  #[cfg(target_os = "linux")]
+  #[test]
+  fn set_priority_applies_to_all_threads() {
+    let pid = Pid::from_u32(std::process::id());
+    let (tid, stop, handle) = spawn_waiting_thread();
+
+    // nice 値を下げる（優先度を上げる）には権限が必要なため、上げる方向のみ確認する
+    let current = procfs_service::read_stat(pid.as_u32()).unwrap().nice;
+    let nice = (current + 1).min(19);
+    platform::set_priority(pid, nice).unwrap();
+
+    let (_, stat) = procfs_service::read_thread(pid.as_u32(), tid).unwrap();
+    assert_eq!(stat.nice, nice);
+    assert_eq!(platform::get_priority(pid).unwrap().nice, Some(nice));
+
+    stop.send(()).unwrap();
+    handle.join().unwrap();
+  }
+
+  #[cfg(target_os = "linux")]
+  #[test]
+  fn set_affinity_applies_to_all_threads() {
+    let pid = Pid::from_u32(std::process::id());
+    let original = platform::get_affinity(pid).unwrap();
+    let (tid, stop, handle) = spawn_waiting_thread();
+
+    let cpus = vec![original[0]];
+    let result = platform::set_affinity(pid, &cpus);
+    let thread_affinity = platform::get_affinity(Pid::from_u32(tid));
+
+    // 他のテストに影響しないよう元に戻す
+    platform::set_affinity(pid, &original).unwrap();
+    stop.send(()).unwrap();
+    handle.join().unwrap();
+
+    result.unwrap();
+    assert_eq!(thread_affinity.unwrap(), cpus);
+  }
+}
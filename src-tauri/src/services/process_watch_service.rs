@@ -0,0 +1,407 @@
+use crate::services::process_service;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use sysinfo::{Pid, Process};
+
+///
+/// 監視対象のプロセスが起動したときのイベント名
+///
+pub const PROCESS_STARTED_EVENT: &str = "process_started";
+
+///
+/// 監視対象のプロセスが終了したときのイベント名
+///
+pub const PROCESS_EXITED_EVENT: &str = "process_exited";
+
+///
+/// 起動し続けるべきプロセスがすべて終了したときのイベント名
+///
+pub const PROCESS_DISAPPEARED_EVENT: &str = "process_disappeared";
+
+///
+/// 監視ルール（指定された条件をすべて満たすプロセスが対象）
+///
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessWatchRule {
+  pub id: String,
+  pub name: Option<String>, // プロセス名（大文字小文字を区別しない完全一致）
+  pub path_pattern: Option<String>, // 実行ファイルのパスに対する正規表現
+  pub pid: Option<u32>,
+  #[serde(default)]
+  pub expect_running: bool, // 常駐を期待する（すべて終了したら `process_disappeared` を通知）
+}
+
+impl ProcessWatchRule {
+  ///
+  /// ## ルールの内容を検証
+  ///
+  pub fn validate(&self) -> Result<(), String> {
+    if self.name.is_none() && self.path_pattern.is_none() && self.pid.is_none() {
+      return Err("Watch rule requires a name, path pattern or pid".to_string());
+    }
+
+    if let Some(pattern) = &self.path_pattern {
+      Regex::new(pattern).map_err(|e| format!("Invalid path pattern: {}", e))?;
+    }
+
+    Ok(())
+  }
+}
+
+///
+/// ライフサイクルの差分計算に使用するプロセスの情報
+///
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessSnapshot {
+  pub pid: u32,
+  pub name: String,
+  pub exe: Option<String>,
+  pub start_time: u64,
+}
+
+impl ProcessSnapshot {
+  pub fn from_process(process: &Process) -> Self {
+    Self {
+      pid: process.pid().as_u32(),
+      name: process.name().to_string_lossy().into_owned(),
+      exe: process.exe().map(|exe| exe.display().to_string()),
+      start_time: process.start_time(),
+    }
+  }
+}
+
+///
+/// 前回のサンプリングからの差分
+///
+#[derive(Debug, Default)]
+pub struct ProcessLifecycleDiff {
+  pub started: Vec<ProcessSnapshot>,
+  pub exited: Vec<ProcessSnapshot>,
+}
+
+///
+/// サンプリングごとのプロセス一覧を保持し、起動・終了を検出する
+///
+#[derive(Debug, Default)]
+pub struct ProcessLifecycleTracker {
+  known: HashMap<u32, ProcessSnapshot>,
+  initialized: bool,
+}
+
+impl ProcessLifecycleTracker {
+  ///
+  /// ## 現在のプロセス一覧との差分を計算
+  ///
+  /// - PID が同じでも開始時刻が異なる場合は、終了と起動の両方として扱う
+  /// - 初回は既存のプロセスを起動として扱わない
+  /// - スレッドの生成・終了はプロセスの起動・終了として扱わない
+  ///
+  pub fn update(&mut self, processes: &HashMap<Pid, Process>) -> ProcessLifecycleDiff {
+    let current: HashMap<u32, ProcessSnapshot> = processes
+      .values()
+      .filter(|process| !process_service::is_thread(process))
+      .map(|process| {
+        (
+          process.pid().as_u32(),
+          ProcessSnapshot::from_process(process),
+        )
+      })
+      .collect();
+
+    self.update_snapshots(current)
+  }
+
+  fn update_snapshots(
+    &mut self,
+    current: HashMap<u32, ProcessSnapshot>,
+  ) -> ProcessLifecycleDiff {
+    let mut diff = ProcessLifecycleDiff::default();
+
+    if self.initialized {
+      for (pid, snapshot) in &current {
+        match self.known.get(pid) {
+          Some(known) if known.start_time == snapshot.start_time => {}
+          _ => diff.started.push(snapshot.clone()),
+        }
+      }
+
+      for (pid, known) in &self.known {
+        match current.get(pid) {
+          Some(snapshot) if snapshot.start_time == known.start_time => {}
+          _ => diff.exited.push(known.clone()),
+        }
+      }
+    }
+
+    self.known = current;
+    self.initialized = true;
+
+    diff
+  }
+
+  ///
+  /// ## 現在実行中のプロセス
+  ///
+  pub fn running(&self) -> impl Iterator<Item = &ProcessSnapshot> {
+    self.known.values()
+  }
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessWatchEvent {
+  pub rule_id: String,
+  #[serde(flatten)]
+  pub process: ProcessSnapshot,
+  pub exit_time: Option<u64>, // 終了を検出した時刻（UNIX エポックからの秒）
+  pub lifetime_secs: Option<u64>, // 起動から終了までの秒数
+}
+
+///
+/// ## 差分を監視ルールと照合し、通知するイベントを作成
+///
+/// - return: `Vec<(&'static str, ProcessWatchEvent)>` イベント名と内容
+///
+pub fn evaluate(
+  rules: &[ProcessWatchRule],
+  diff: &ProcessLifecycleDiff,
+  tracker: &ProcessLifecycleTracker,
+  exit_time: u64,
+) -> Vec<(&'static str, ProcessWatchEvent)> {
+  let mut events = Vec::new();
+
+  for rule in rules {
+    // 保存時に検証しているため、ここで不正なパターンは無視する
+    let path_regex = match rule.path_pattern.as_deref().map(Regex::new) {
+      Some(Ok(regex)) => Some(regex),
+      Some(Err(_)) => continue,
+      None => None,
+    };
+    let matches =
+      |snapshot: &ProcessSnapshot| is_match(rule, path_regex.as_ref(), snapshot);
+
+    for snapshot in diff.started.iter().filter(|snapshot| matches(snapshot)) {
+      events.push((
+        PROCESS_STARTED_EVENT,
+        ProcessWatchEvent {
+          rule_id: rule.id.clone(),
+          process: snapshot.clone(),
+          exit_time: None,
+          lifetime_secs: None,
+        },
+      ));
+    }
+
+    let exited: Vec<&ProcessSnapshot> = diff
+      .exited
+      .iter()
+      .filter(|snapshot| matches(snapshot))
+      .collect();
+
+    for snapshot in &exited {
+      events.push((
+        PROCESS_EXITED_EVENT,
+        ProcessWatchEvent {
+          rule_id: rule.id.clone(),
+          process: (*snapshot).clone(),
+          exit_time: Some(exit_time),
+          lifetime_secs: Some(exit_time.saturating_sub(snapshot.start_time)),
+        },
+      ));
+    }
+
+    // 常駐を期待するプロセスが 1つも残っていない場合は異常終了とみなす
+    if rule.expect_running && !exited.is_empty() && !tracker.running().any(matches) {
+      let last = exited[exited.len() - 1];
+      events.push((
+        PROCESS_DISAPPEARED_EVENT,
+        ProcessWatchEvent {
+          rule_id: rule.id.clone(),
+          process: last.clone(),
+          exit_time: Some(exit_time),
+          lifetime_secs: Some(exit_time.saturating_sub(last.start_time)),
+        },
+      ));
+    }
+  }
+
+  events
+}
+
+fn is_match(
+  rule: &ProcessWatchRule,
+  path_regex: Option<&Regex>,
+  snapshot: &ProcessSnapshot,
+) -> bool {
+  rule.pid.map_or(true, |pid| pid == snapshot.pid)
+    && rule
+      .name
+      .as_ref()
+      .map_or(true, |name| name.eq_ignore_ascii_case(&snapshot.name))
+    && path_regex.map_or(true, |regex| {
+      snapshot
+        .exe
+        .as_ref()
+        .map_or(false, |exe| regex.is_match(exe))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn snapshot(pid: u32, name: &str, start_time: u64) -> ProcessSnapshot {
+    ProcessSnapshot {
+      pid,
+      name: name.to_string(),
+      exe: Some(format!("/usr/bin/{}", name)),
+      start_time,
+    }
+  }
+
+  fn update(
+    tracker: &mut ProcessLifecycleTracker,
+    snapshots: &[ProcessSnapshot],
+  ) -> ProcessLifecycleDiff {
+    let mut diff = tracker.update_snapshots(
+      snapshots
+        .iter()
+        .map(|snapshot| (snapshot.pid, snapshot.clone()))
+        .collect(),
+    );
+    // `HashMap` の列挙順に依存しないよう並べ替える
+    diff.started.sort_by_key(|snapshot| snapshot.pid);
+    diff.exited.sort_by_key(|snapshot| snapshot.pid);
+    diff
+  }
+
+  fn pids(snapshots: &[ProcessSnapshot]) -> Vec<(u32, u64)> {
+    snapshots
+      .iter()
+      .map(|snapshot| (snapshot.pid, snapshot.start_time))
+      .collect()
+  }
+
+  fn rule(name: &str, expect_running: bool) -> ProcessWatchRule {
+    ProcessWatchRule {
+      id: name.to_string(),
+      name: Some(name.to_string()),
+      path_pattern: None,
+      pid: None,
+      expect_running,
+    }
+  }
+
+  #[test]
+  fn first_update_does_not_report_existing_processes() {
+    let mut tracker = ProcessLifecycleTracker::default();
+
+    let diff = update(
+      &mut tracker,
+      &[snapshot(1, "init", 0), snapshot(100, "nginx", 10)],
+    );
+
+    assert!(diff.started.is_empty());
+    assert!(diff.exited.is_empty());
+    assert_eq!(tracker.running().count(), 2);
+  }
+
+  #[test]
+  fn detects_started_and_exited_processes() {
+    let mut tracker = ProcessLifecycleTracker::default();
+    update(
+      &mut tracker,
+      &[snapshot(1, "init", 0), snapshot(100, "nginx", 10)],
+    );
+
+    let diff = update(
+      &mut tracker,
+      &[snapshot(1, "init", 0), snapshot(200, "redis", 20)],
+    );
+
+    assert_eq!(pids(&diff.started), [(200, 20)]);
+    assert_eq!(pids(&diff.exited), [(100, 10)]);
+  }
+
+  #[test]
+  fn reused_pid_is_reported_as_exit_and_start() {
+    let mut tracker = ProcessLifecycleTracker::default();
+    update(&mut tracker, &[snapshot(100, "nginx", 10)]);
+
+    // 同じ PID を別のプロセスが再利用した
+    let diff = update(&mut tracker, &[snapshot(100, "nginx", 50)]);
+
+    assert_eq!(pids(&diff.started), [(100, 50)]);
+    assert_eq!(pids(&diff.exited), [(100, 10)]);
+
+    let diff = update(&mut tracker, &[snapshot(100, "nginx", 50)]);
+
+    assert!(diff.started.is_empty());
+    assert!(diff.exited.is_empty());
+  }
+
+  #[test]
+  fn evaluate_reports_lifetime_of_exited_process() {
+    let mut tracker = ProcessLifecycleTracker::default();
+    update(&mut tracker, &[snapshot(100, "nginx", 10)]);
+    let diff = update(&mut tracker, &[snapshot(200, "NGINX", 40)]);
+
+    let events = evaluate(&[rule("nginx", false)], &diff, &tracker, 70);
+
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].0, PROCESS_STARTED_EVENT);
+    assert_eq!(events[0].1.process.pid, 200);
+    assert_eq!(events[0].1.exit_time, None);
+    assert_eq!(events[1].0, PROCESS_EXITED_EVENT);
+    assert_eq!(events[1].1.process.pid, 100);
+    assert_eq!(events[1].1.exit_time, Some(70));
+    assert_eq!(events[1].1.lifetime_secs, Some(60));
+  }
+
+  #[test]
+  fn evaluate_reports_disappearance_only_when_no_instance_remains() {
+    let mut tracker = ProcessLifecycleTracker::default();
+    update(
+      &mut tracker,
+      &[snapshot(100, "worker", 10), snapshot(101, "worker", 10)],
+    );
+    let rules = [rule("worker", true)];
+
+    let diff = update(&mut tracker, &[snapshot(101, "worker", 10)]);
+    let events = evaluate(&rules, &diff, &tracker, 30);
+
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].0, PROCESS_EXITED_EVENT);
+
+    let diff = update(&mut tracker, &[]);
+    let events = evaluate(&rules, &diff, &tracker, 40);
+
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[1].0, PROCESS_DISAPPEARED_EVENT);
+    assert_eq!(events[1].1.process.pid, 101);
+    assert_eq!(events[1].1.lifetime_secs, Some(30));
+  }
+
+  #[test]
+  fn evaluate_matches_pid_reuse_with_path_pattern() {
+    let mut tracker = ProcessLifecycleTracker::default();
+    update(&mut tracker, &[snapshot(100, "nginx", 10)]);
+    let diff = update(&mut tracker, &[snapshot(100, "nginx", 50)]);
+    let rules = [ProcessWatchRule {
+      id: "nginx".to_string(),
+      name: None,
+      path_pattern: Some("^/usr/bin/nginx$".to_string()),
+      pid: Some(100),
+      expect_running: true,
+    }];
+
+    let events = evaluate(&rules, &diff, &tracker, 60);
+
+    // 同じ PID で別のプロセスが動いているため、消失とはみなさない
+    let kinds: Vec<_> = events.iter().map(|(kind, _)| *kind).collect();
+    assert_eq!(kinds, [PROCESS_STARTED_EVENT, PROCESS_EXITED_EVENT]);
+  }
+}
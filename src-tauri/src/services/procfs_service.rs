@@ -0,0 +1,342 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+///
+/// procfs のマウントポイント
+///
+pub const PROC_ROOT: &str = "/proc";
+
+///
+/// `/proc/<pid>/stat` から取り出した値
+///
+#[derive(Debug, Default, Clone)]
+pub struct ProcStat {
+  pub state: char,
+  pub ppid: u32,
+  pub utime: u64,
+  pub stime: u64,
+  pub nice: i32,
+  pub num_threads: u64,
+  pub start_time: u64,
+}
+
+///
+/// ## `/proc/<pid>` のパスを取得
+///
+pub fn pid_dir(pid: u32) -> PathBuf {
+  PathBuf::from(PROC_ROOT).join(pid.to_string())
+}
+
+///
+/// ## `/proc/<pid>/stat` を読み込む
+///
+pub fn read_stat(pid: u32) -> Result<ProcStat, String> {
+  let path = pid_dir(pid).join("stat");
+  let content = fs::read_to_string(&path)
+    .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+
+  parse_stat(&content).ok_or_else(|| format!("Failed to parse {}", path.display()))
+}
+
+///
+/// ## プロセスに属するスレッドの ID を取得
+///
+/// - `/proc/<pid>/task` 配下のディレクトリ名がスレッド ID
+///
+pub fn list_thread_ids(pid: u32) -> Result<Vec<u32>, String> {
+  let path = pid_dir(pid).join("task");
+  let entries = fs::read_dir(&path)
+    .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+
+  let mut tids: Vec<u32> = entries
+    .flatten()
+    .filter_map(|entry| entry.file_name().to_string_lossy().parse::<u32>().ok())
+    .collect();
+
+  tids.sort_unstable();
+  Ok(tids)
+}
+
+///
+/// ## `/proc/<pid>/task/<tid>` の `stat` とスレッド名（`comm`）を読み込む
+///
+pub fn read_thread(pid: u32, tid: u32) -> Option<(String, ProcStat)> {
+  let dir = pid_dir(pid).join("task").join(tid.to_string());
+
+  let stat = parse_stat(&fs::read_to_string(dir.join("stat")).ok()?)?;
+  let name = fs::read_to_string(dir.join("comm"))
+    .map(|comm| comm.trim().to_string())
+    .unwrap_or_default();
+
+  Some((name, stat))
+}
+
+///
+/// ## `stat` ファイルの内容を解析
+///
+/// - プロセス名（2番目のフィールド）は空白や括弧を含みうるため、最後の `)` 以降を分割する
+///
+pub fn parse_stat(content: &str) -> Option<ProcStat> {
+  let rest = &content[content.rfind(')')? + 1..];
+  let fields: Vec<&str> = rest.split_whitespace().collect();
+
+  // `fields[0]` は 3番目のフィールド（state）に対応する
+  let field = |index: usize| fields.get(index - 3).copied();
+
+  Some(ProcStat {
+    state: field(3)?.chars().next()?,
+    ppid: field(4)?.parse().ok()?,
+    utime: field(14)?.parse().ok()?,
+    stime: field(15)?.parse().ok()?,
+    nice: field(19)?.parse().ok()?,
+    num_threads: field(20)?.parse().ok()?,
+    start_time: field(22)?.parse().ok()?,
+  })
+}
+
+///
+/// ## 開いているファイルディスクリプタの数を取得
+///
+/// - 他ユーザーのプロセスは権限不足で読めないため `None` を返す
+///
+pub fn count_open_fds(pid: u32) -> Option<usize> {
+  fs::read_dir(pid_dir(pid).join("fd"))
+    .ok()
+    .map(|entries| entries.count())
+}
+
+///
+/// ## 1秒あたりのクロックティック数（`utime` / `stime` の単位）
+///
+#[cfg(target_os = "linux")]
+pub fn clock_ticks_per_second() -> u64 {
+  match unsafe { libc::sysconf(libc::_SC_CLK_TCK) } {
+    ticks if ticks > 0 => ticks as u64,
+    _ => 100,
+  }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn clock_ticks_per_second() -> u64 {
+  100
+}
+
+///
+/// `/proc/<pid>/status` と `smaps_rollup` から取り出したメモリ使用量（bytes）
+///
+#[derive(Debug, Default, Clone)]
+pub struct ProcMemory {
+  pub rss: Option<u64>,
+  pub virtual_size: Option<u64>,
+  pub rss_anon: Option<u64>,
+  pub rss_file: Option<u64>,
+  pub rss_shmem: Option<u64>,
+  pub swap: Option<u64>,
+  pub pss: Option<u64>,
+  pub shared_clean: Option<u64>,
+  pub shared_dirty: Option<u64>,
+  pub private_clean: Option<u64>,
+  pub private_dirty: Option<u64>,
+}
+
+///
+/// ## プロセスのメモリ使用量の内訳を取得
+///
+/// - `smaps_rollup` は他ユーザーのプロセスでは読めないため、その値は `None` になる
+///
+pub fn read_memory(pid: u32) -> Option<ProcMemory> {
+  read_memory_in(&pid_dir(pid))
+}
+
+fn read_memory_in(dir: &Path) -> Option<ProcMemory> {
+  let status = fs::read_to_string(dir.join("status")).ok()?;
+
+  let mut memory = ProcMemory::default();
+  apply_kb_fields(&status, &mut memory);
+
+  if let Ok(smaps_rollup) = fs::read_to_string(dir.join("smaps_rollup")) {
+    apply_kb_fields(&smaps_rollup, &mut memory);
+  }
+
+  Some(memory)
+}
+
+///
+/// ## `<Key>: <value> kB` 形式の行を解析して反映
+///
+/// - `status` と `smaps_rollup` の両方で使用する（`Swap` は `smaps_rollup` の値を優先）
+///
+pub fn apply_kb_fields(content: &str, memory: &mut ProcMemory) {
+  for line in content.lines() {
+    let (key, value) = match line.split_once(':') {
+      Some((key, value)) => (key.trim(), value.trim()),
+      None => continue,
+    };
+    let bytes = match value.trim_end_matches("kB").trim().parse::<u64>() {
+      Ok(kb) => Some(kb * 1024),
+      Err(_) => continue,
+    };
+
+    match key {
+      "VmRSS" => memory.rss = bytes,
+      "VmSize" => memory.virtual_size = bytes,
+      "RssAnon" => memory.rss_anon = bytes,
+      "RssFile" => memory.rss_file = bytes,
+      "RssShmem" => memory.rss_shmem = bytes,
+      "VmSwap" | "Swap" => memory.swap = bytes,
+      "Pss" => memory.pss = bytes,
+      "Shared_Clean" => memory.shared_clean = bytes,
+      "Shared_Dirty" => memory.shared_dirty = bytes,
+      "Private_Clean" => memory.private_clean = bytes,
+      "Private_Dirty" => memory.private_dirty = bytes,
+      _ => {}
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::sync::atomic::{AtomicUsize, Ordering};
+
+  // プロセス名に空白と `)` を含む `stat`
+  const STAT_FIXTURE: &str = "4242 (my (odd) app) S 1 4242 4242 0 -1 4194560 1200 0 3 0 \
+    250 75 0 0 20 -5 8 0 123456 104857600 2560 18446744073709551615 1 1 0 0 0 0 0 \
+    4096 0 0 0 0 17 3 0 0 0 0 0\n";
+
+  const STATUS_FIXTURE: &str = "\
+Name:\tmy app
+State:\tS (sleeping)
+VmSize:\t  102400 kB
+VmRSS:\t   10240 kB
+RssAnon:\t    6144 kB
+RssFile:\t    3072 kB
+RssShmem:\t    1024 kB
+VmSwap:\t     512 kB
+Threads:\t8
+";
+
+  const SMAPS_ROLLUP_FIXTURE: &str = "\
+55d0c0a00000-7ffd1a5ff000 ---p 00000000 00:00 0                          [rollup]
+Rss:               10240 kB
+Pss:                8000 kB
+Shared_Clean:       2048 kB
+Shared_Dirty:        128 kB
+Private_Clean:      1024 kB
+Private_Dirty:      7040 kB
+Swap:                768 kB
+";
+
+  ///
+  /// テストごとに一時ディレクトリへ `/proc/<pid>` のファイルを作成し、終了時に削除する
+  ///
+  struct ProcFixture {
+    dir: PathBuf,
+  }
+
+  impl ProcFixture {
+    fn new(files: &[(&str, &str)]) -> Self {
+      static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+      let dir = std::env::temp_dir().join(format!(
+        "procfs_fixture_{}_{}",
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::SeqCst)
+      ));
+      fs::create_dir_all(&dir).unwrap();
+      for (name, content) in files {
+        fs::write(dir.join(name), content).unwrap();
+      }
+
+      Self { dir }
+    }
+  }
+
+  impl Drop for ProcFixture {
+    fn drop(&mut self) {
+      let _ = fs::remove_dir_all(&self.dir);
+    }
+  }
+
+  #[test]
+  fn parse_stat_handles_names_with_spaces_and_parentheses() {
+    let stat = parse_stat(STAT_FIXTURE).unwrap();
+
+    assert_eq!(stat.state, 'S');
+    assert_eq!(stat.ppid, 1);
+    assert_eq!(stat.utime, 250);
+    assert_eq!(stat.stime, 75);
+    assert_eq!(stat.nice, -5);
+    assert_eq!(stat.num_threads, 8);
+    assert_eq!(stat.start_time, 123456);
+  }
+
+  #[test]
+  fn parse_stat_rejects_truncated_content() {
+    assert!(parse_stat("4242 (app) S 1 4242").is_none());
+    assert!(parse_stat("4242 app S 1").is_none());
+    assert!(parse_stat("").is_none());
+  }
+
+  #[test]
+  fn apply_kb_fields_converts_to_bytes() {
+    let mut memory = ProcMemory::default();
+    apply_kb_fields(STATUS_FIXTURE, &mut memory);
+
+    assert_eq!(memory.virtual_size, Some(102400 * 1024));
+    assert_eq!(memory.rss, Some(10240 * 1024));
+    assert_eq!(memory.rss_anon, Some(6144 * 1024));
+    assert_eq!(memory.rss_file, Some(3072 * 1024));
+    assert_eq!(memory.rss_shmem, Some(1024 * 1024));
+    assert_eq!(memory.swap, Some(512 * 1024));
+    // `status` には含まれない
+    assert_eq!(memory.pss, None);
+  }
+
+  #[test]
+  fn apply_kb_fields_ignores_lines_without_numbers() {
+    let mut memory = ProcMemory::default();
+    apply_kb_fields("VmRSS:\tunknown\nnot a field\nPss: 4 kB\n", &mut memory);
+
+    assert_eq!(memory.rss, None);
+    assert_eq!(memory.pss, Some(4096));
+  }
+
+  #[test]
+  fn read_memory_prefers_smaps_rollup_swap() {
+    let fixture = ProcFixture::new(&[
+      ("status", STATUS_FIXTURE),
+      ("smaps_rollup", SMAPS_ROLLUP_FIXTURE),
+    ]);
+
+    let memory = read_memory_in(&fixture.dir).unwrap();
+
+    assert_eq!(memory.rss, Some(10240 * 1024));
+    assert_eq!(memory.rss_anon, Some(6144 * 1024));
+    assert_eq!(memory.swap, Some(768 * 1024));
+    assert_eq!(memory.pss, Some(8000 * 1024));
+    assert_eq!(memory.shared_clean, Some(2048 * 1024));
+    assert_eq!(memory.shared_dirty, Some(128 * 1024));
+    assert_eq!(memory.private_clean, Some(1024 * 1024));
+    assert_eq!(memory.private_dirty, Some(7040 * 1024));
+  }
+
+  #[test]
+  fn read_memory_without_smaps_rollup_uses_status_only() {
+    let fixture = ProcFixture::new(&[("status", STATUS_FIXTURE)]);
+
+    let memory = read_memory_in(&fixture.dir).unwrap();
+
+    assert_eq!(memory.rss, Some(10240 * 1024));
+    assert_eq!(memory.swap, Some(512 * 1024));
+    assert_eq!(memory.pss, None);
+    assert_eq!(memory.private_dirty, None);
+  }
+
+  #[test]
+  fn read_memory_without_status_is_none() {
+    let fixture = ProcFixture::new(&[("smaps_rollup", SMAPS_ROLLUP_FIXTURE)]);
+
+    assert!(read_memory_in(&fixture.dir).is_none());
+  }
+}
@@ -0,0 +1,721 @@
+use crate::services::graphic_service::{self, NameValue};
+use crate::{log_error, log_info, log_internal, log_warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::io;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use sysinfo::{Components, Disks, Networks, System};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+///
+/// メトリクス名の接頭辞
+///
+const METRIC_PREFIX: &str = "hardware_monitor";
+
+///
+/// リクエストヘッダーの最大サイズ
+///
+const MAX_REQUEST_HEAD_BYTES: usize = 8192;
+
+///
+/// リクエストの受信を待つ時間
+///
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+///
+/// 停止したサーバーのポートが解放されるのを待つ時間
+///
+const REBIND_TIMEOUT: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PrometheusSettings {
+  pub enabled: bool,
+  pub bind_address: String, // 既定では他のホストから接続できないよう `127.0.0.1`
+  pub port: u16,
+}
+
+impl Default for PrometheusSettings {
+  fn default() -> Self {
+    Self {
+      enabled: false,
+      bind_address: "127.0.0.1".to_string(),
+      port: 9184,
+    }
+  }
+}
+
+impl PrometheusSettings {
+  ///
+  /// ## 待ち受けるアドレス
+  ///
+  pub fn socket_addr(&self) -> Result<SocketAddr, String> {
+    let ip: IpAddr = self
+      .bind_address
+      .trim()
+      .parse()
+      .map_err(|e| format!("Invalid bind address {}: {}", self.bind_address, e))?;
+    Ok(SocketAddr::new(ip, self.port))
+  }
+}
+
+///
+/// 起動中の `/metrics` エンドポイント
+///
+struct PrometheusServer {
+  settings: PrometheusSettings,
+  task: tauri::async_runtime::JoinHandle<()>,
+}
+
+///
+/// Prometheus のテキスト形式でメトリクスを公開する HTTP サーバーを管理する
+///
+pub struct PrometheusExporter {
+  system: Arc<Mutex<System>>,
+  server: tokio::sync::Mutex<Option<PrometheusServer>>,
+}
+
+impl PrometheusExporter {
+  pub fn new(system: Arc<Mutex<System>>) -> Self {
+    Self {
+      system,
+      server: tokio::sync::Mutex::new(None),
+    }
+  }
+
+  ///
+  /// ## 設定に合わせてサーバーを起動・停止
+  ///
+  /// - アドレスかポートが変わった場合は、新しいアドレスで待ち受けてから古いサーバーを停止する
+  /// - ポートを変えずにアドレスだけ変えた場合（`127.0.0.1` → `0.0.0.0` など）は、古いサーバーを停止してから待ち受ける
+  /// - 待ち受けに失敗した場合は、起動中のサーバーを維持してエラーを返す
+  ///
+  pub async fn apply(&self, settings: &PrometheusSettings) -> Result<(), String> {
+    let mut server = self.server.lock().await;
+
+    if !settings.enabled {
+      if let Some(server) = server.take() {
+        server.task.abort();
+        log_info!("stopped", "PrometheusExporter::apply", None::<&str>);
+      }
+      return Ok(());
+    }
+
+    if server
+      .as_ref()
+      .map_or(false, |server| &server.settings == settings)
+    {
+      return Ok(());
+    }
+
+    let addr = settings.socket_addr()?;
+    let listener = match bind(addr) {
+      Ok(listener) => listener,
+      Err(e)
+        if e.kind() == io::ErrorKind::AddrInUse
+          && server
+            .as_ref()
+            .map_or(false, |server| server.settings.port == settings.port) =>
+      {
+        let previous = server.take().unwrap();
+        previous.task.abort();
+
+        match bind_after_release(addr).await {
+          Ok(listener) => listener,
+          Err(e) => {
+            // 新しいアドレスで待ち受けられない場合は、元のアドレスで待ち受け直す
+            let restored = match previous.settings.socket_addr() {
+              Ok(addr) => bind_after_release(addr).await.map_err(|e| e.to_string()),
+              Err(e) => Err(e),
+            };
+            match restored {
+              Ok(listener) => *server = Some(self.spawn(previous.settings, listener)),
+              Err(e) => {
+                log_error!("restore_failed", "PrometheusExporter::apply", Some(e));
+              }
+            }
+            return Err(format!("Failed to listen on {}: {}", addr, e));
+          }
+        }
+      }
+      Err(e) => return Err(format!("Failed to listen on {}: {}", addr, e)),
+    };
+
+    if let Some(previous) = server.replace(self.spawn(settings.clone(), listener)) {
+      previous.task.abort();
+    }
+
+    log_info!(
+      "started",
+      "PrometheusExporter::apply",
+      Some(format!("http://{}/metrics", addr))
+    );
+
+    Ok(())
+  }
+
+  fn spawn(
+    &self,
+    settings: PrometheusSettings,
+    listener: std::net::TcpListener,
+  ) -> PrometheusServer {
+    let system = Arc::clone(&self.system);
+
+    let task = tauri::async_runtime::spawn(async move {
+      let listener = match TcpListener::from_std(listener) {
+        Ok(listener) => listener,
+        Err(e) => {
+          log_error!("listen_failed", "prometheus_service", Some(e.to_string()));
+          return;
+        }
+      };
+
+      loop {
+        let stream = match listener.accept().await {
+          Ok((stream, _)) => stream,
+          Err(e) => {
+            log_warn!("accept_failed", "prometheus_service", Some(e.to_string()));
+            continue;
+          }
+        };
+
+        let system = Arc::clone(&system);
+        tokio::spawn(async move {
+          if let Err(e) = handle_connection(stream, system).await {
+            log_warn!("request_failed", "prometheus_service", Some(e));
+          }
+        });
+      }
+    });
+
+    PrometheusServer { settings, task }
+  }
+}
+
+fn bind(addr: SocketAddr) -> io::Result<std::net::TcpListener> {
+  let listener = std::net::TcpListener::bind(addr)?;
+  listener.set_nonblocking(true)?;
+  Ok(listener)
+}
+
+///
+/// ## 停止したサーバーがポートを解放するまで待ってから待ち受ける
+///
+/// - 停止したタスクはランタイムが破棄するまでソケットを保持しているため、`REBIND_TIMEOUT` まで再試行する
+///
+async fn bind_after_release(addr: SocketAddr) -> io::Result<std::net::TcpListener> {
+  let deadline = tokio::time::Instant::now() + REBIND_TIMEOUT;
+
+  loop {
+    match bind(addr) {
+      Err(e)
+        if e.kind() == io::ErrorKind::AddrInUse
+          && tokio::time::Instant::now() < deadline =>
+      {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+      }
+      result => return result,
+    }
+  }
+}
+
+///
+/// ## リクエストを読み込み、`GET /metrics` にのみ応答する
+///
+async fn handle_connection(
+  mut stream: TcpStream,
+  system: Arc<Mutex<System>>,
+) -> Result<(), String> {
+  let head = tokio::time::timeout(REQUEST_TIMEOUT, read_request_head(&mut stream))
+    .await
+    .map_err(|_| "Timed out reading request".to_string())??;
+
+  let mut parts = head.lines().next().unwrap_or_default().split_whitespace();
+  let method = parts.next().unwrap_or_default();
+  let path = parts.next().unwrap_or_default();
+  let path = path.split('?').next().unwrap_or_default();
+
+  let response = match (method, path) {
+    ("GET" | "HEAD", "/metrics") => {
+      let gpu = GpuSnapshot::collect().await;
+      let body = tokio::task::spawn_blocking(move || {
+        // サンプリングのループを止めないよう、一覧の取得はロックの外で行う
+        let disks = Disks::new_with_refreshed_list();
+        let networks = Networks::new_with_refreshed_list();
+        let components = Components::new_with_refreshed_list();
+
+        let sys = system.lock().unwrap();
+        render_metrics(&sys, &disks, &networks, &components, &gpu)
+      })
+      .await
+      .map_err(|e| e.to_string())?;
+
+      http_response(
+        "200 OK",
+        "text/plain; version=0.0.4; charset=utf-8",
+        &body,
+        method == "HEAD",
+      )
+    }
+    (_, "/metrics") => http_response("405 Method Not Allowed", "text/plain", "", false),
+    _ => http_response("404 Not Found", "text/plain", "Not Found\n", false),
+  };
+
+  stream
+    .write_all(response.as_bytes())
+    .await
+    .map_err(|e| e.to_string())?;
+  stream.shutdown().await.map_err(|e| e.to_string())
+}
+
+async fn read_request_head(stream: &mut TcpStream) -> Result<String, String> {
+  let mut buffer = Vec::with_capacity(1024);
+  let mut chunk = [0u8; 1024];
+
+  loop {
+    let read = stream.read(&mut chunk).await.map_err(|e| e.to_string())?;
+    if read == 0 {
+      break;
+    }
+    buffer.extend_from_slice(&chunk[..read]);
+
+    if buffer.windows(4).any(|window| window == b"\r\n\r\n") {
+      break;
+    }
+    if buffer.len() > MAX_REQUEST_HEAD_BYTES {
+      return Err("Request header too large".to_string());
+    }
+  }
+
+  Ok(String::from_utf8_lossy(&buffer).to_string())
+}
+
+fn http_response(
+  status: &str,
+  content_type: &str,
+  body: &str,
+  head_only: bool,
+) -> String {
+  format!(
+    "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+    status,
+    content_type,
+    body.len(),
+    if head_only { "" } else { body }
+  )
+}
+
+///
+/// スクレイプ時に取得した GPU の情報（Nvidia 限定、取得できない場合は空）
+///
+#[derive(Debug, Default)]
+pub struct GpuSnapshot {
+  pub usage: Option<f32>, // %
+  pub temperatures: Vec<NameValue>,
+}
+
+impl GpuSnapshot {
+  async fn collect() -> Self {
+    Self {
+      usage: graphic_service::get_nvidia_gpu_usage()
+        .await
+        .ok()
+        .map(|usage| usage * 100.0),
+      temperatures: graphic_service::get_nvidia_gpu_temperature()
+        .await
+        .unwrap_or_default(),
+    }
+  }
+}
+
+///
+/// Prometheus のテキスト形式の出力
+///
+#[derive(Default)]
+struct Exposition {
+  output: String,
+}
+
+impl Exposition {
+  ///
+  /// ## メトリクスの `HELP` と `TYPE` を出力
+  ///
+  fn family(&mut self, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(self.output, "# HELP {}_{} {}", METRIC_PREFIX, name, help);
+    let _ = writeln!(self.output, "# TYPE {}_{} {}", METRIC_PREFIX, name, kind);
+  }
+
+  fn sample(&mut self, name: &str, labels: &[(&str, &str)], value: f64) {
+    if !value.is_finite() {
+      return;
+    }
+
+    let _ = write!(self.output, "{}_{}", METRIC_PREFIX, name);
+    if !labels.is_empty() {
+      let labels = labels
+        .iter()
+        .map(|(key, value)| format!("{}=\"{}\"", key, escape_label_value(value)))
+        .collect::<Vec<_>>()
+        .join(",");
+      let _ = write!(self.output, "{{{}}}", labels);
+    }
+    let _ = writeln!(self.output, " {}", value);
+  }
+}
+
+fn escape_label_value(value: &str) -> String {
+  value
+    .replace('\\', "\\\\")
+    .replace('"', "\\\"")
+    .replace('\n', "\\n")
+}
+
+///
+/// ## 重複するラベルに連番を付けて一意にする
+///
+/// - 同じ名前のセンサーが複数ある場合（NVMe の `Composite` など）に系列が重複しないようにする
+/// - 2 つ目以降に ` #2`、` #3` と出現順に付けるため、列挙順が変わらない限り同じラベルになる
+///
+fn unique_labels<'a>(labels: impl Iterator<Item = &'a str>) -> Vec<String> {
+  let mut counts: HashMap<&str, usize> = HashMap::new();
+
+  labels
+    .map(|label| {
+      let count = counts.entry(label).or_insert(0);
+      *count += 1;
+      if *count == 1 {
+        label.to_string()
+      } else {
+        format!("{} #{}", label, count)
+      }
+    })
+    .collect()
+}
+
+///
+/// ネットワークのインターフェースごとの累計値を取得する関数
+///
+type NetworkCounter = fn(&sysinfo::NetworkData) -> u64;
+
+///
+/// ## メトリクスを Prometheus のテキスト形式で出力
+///
+/// - ラベルの値はエスケープする
+/// - 値が有限でないサンプルは出力しない
+///
+pub fn render_metrics(
+  sys: &System,
+  disks: &Disks,
+  networks: &Networks,
+  components: &Components,
+  gpu: &GpuSnapshot,
+) -> String {
+  let mut exposition = Exposition::default();
+
+  // CPU
+  exposition.family(
+    "cpu_usage_total_percent",
+    "gauge",
+    "Overall CPU usage across all cores in percent.",
+  );
+  exposition.sample(
+    "cpu_usage_total_percent",
+    &[],
+    sys.global_cpu_usage() as f64,
+  );
+
+  exposition.family("cpu_usage_percent", "gauge", "CPU core usage in percent.");
+  for (index, cpu) in sys.cpus().iter().enumerate() {
+    exposition.sample(
+      "cpu_usage_percent",
+      &[("cpu", &index.to_string())],
+      cpu.cpu_usage() as f64,
+    );
+  }
+
+  exposition.family(
+    "cpu_frequency_hertz",
+    "gauge",
+    "Current CPU core frequency in hertz.",
+  );
+  for (index, cpu) in sys.cpus().iter().enumerate() {
+    exposition.sample(
+      "cpu_frequency_hertz",
+      &[("cpu", &index.to_string())],
+      cpu.frequency() as f64 * 1_000_000.0,
+    );
+  }
+
+  // メモリ・スワップ
+  exposition.family(
+    "memory_total_bytes",
+    "gauge",
+    "Total physical memory in bytes.",
+  );
+  exposition.sample("memory_total_bytes", &[], sys.total_memory() as f64);
+  exposition.family(
+    "memory_used_bytes",
+    "gauge",
+    "Used physical memory in bytes.",
+  );
+  exposition.sample("memory_used_bytes", &[], sys.used_memory() as f64);
+  exposition.family(
+    "memory_available_bytes",
+    "gauge",
+    "Available physical memory in bytes.",
+  );
+  exposition.sample("memory_available_bytes", &[], sys.available_memory() as f64);
+  exposition.family("swap_total_bytes", "gauge", "Total swap space in bytes.");
+  exposition.sample("swap_total_bytes", &[], sys.total_swap() as f64);
+  exposition.family("swap_used_bytes", "gauge", "Used swap space in bytes.");
+  exposition.sample("swap_used_bytes", &[], sys.used_swap() as f64);
+
+  // GPU
+  if let Some(usage) = gpu.usage {
+    exposition.family("gpu_usage_percent", "gauge", "GPU usage in percent.");
+    exposition.sample("gpu_usage_percent", &[], usage as f64);
+  }
+  if !gpu.temperatures.is_empty() {
+    exposition.family(
+      "gpu_temperature_celsius",
+      "gauge",
+      "GPU temperature in degrees Celsius.",
+    );
+    let names = unique_labels(gpu.temperatures.iter().map(|t| t.name.as_str()));
+    for (name, temperature) in names.iter().zip(&gpu.temperatures) {
+      exposition.sample(
+        "gpu_temperature_celsius",
+        &[("gpu", name)],
+        temperature.value,
+      );
+    }
+  }
+
+  // センサー
+  exposition.family(
+    "sensor_temperature_celsius",
+    "gauge",
+    "Hardware sensor temperature in degrees Celsius.",
+  );
+  let sensors = unique_labels(components.iter().map(|component| component.label()));
+  for (sensor, component) in sensors.iter().zip(components.iter()) {
+    exposition.sample(
+      "sensor_temperature_celsius",
+      &[("sensor", sensor)],
+      component.temperature() as f64,
+    );
+  }
+
+  exposition.family(
+    "sensor_critical_temperature_celsius",
+    "gauge",
+    "Critical temperature reported by the hardware sensor in degrees Celsius.",
+  );
+  for (sensor, component) in sensors.iter().zip(components.iter()) {
+    if let Some(critical) = component.critical() {
+      exposition.sample(
+        "sensor_critical_temperature_celsius",
+        &[("sensor", sensor)],
+        critical as f64,
+      );
+    }
+  }
+
+  // ディスク
+  let disks: Vec<_> = disks
+    .iter()
+    .map(|disk| {
+      (
+        disk.name().to_string_lossy().to_string(),
+        disk.mount_point().to_string_lossy().to_string(),
+        disk.file_system().to_string_lossy().to_string(),
+        disk.total_space(),
+        disk.available_space(),
+      )
+    })
+    .collect();
+
+  exposition.family("disk_total_bytes", "gauge", "Disk size in bytes.");
+  for (device, mount_point, fstype, total, _) in &disks {
+    exposition.sample(
+      "disk_total_bytes",
+      &[
+        ("device", device),
+        ("mountpoint", mount_point),
+        ("fstype", fstype),
+      ],
+      *total as f64,
+    );
+  }
+
+  exposition.family(
+    "disk_available_bytes",
+    "gauge",
+    "Disk space available in bytes.",
+  );
+  for (device, mount_point, fstype, _, available) in &disks {
+    exposition.sample(
+      "disk_available_bytes",
+      &[
+        ("device", device),
+        ("mountpoint", mount_point),
+        ("fstype", fstype),
+      ],
+      *available as f64,
+    );
+  }
+
+  // ネットワーク
+  let network_counters: [(&str, &str, NetworkCounter); 6] = [
+    (
+      "network_received_bytes_total",
+      "Total bytes received by the network interface.",
+      |data| data.total_received(),
+    ),
+    (
+      "network_transmitted_bytes_total",
+      "Total bytes transmitted by the network interface.",
+      |data| data.total_transmitted(),
+    ),
+    (
+      "network_received_packets_total",
+      "Total packets received by the network interface.",
+      |data| data.total_packets_received(),
+    ),
+    (
+      "network_transmitted_packets_total",
+      "Total packets transmitted by the network interface.",
+      |data| data.total_packets_transmitted(),
+    ),
+    (
+      "network_receive_errors_total",
+      "Total receive errors on the network interface.",
+      |data| data.total_errors_on_received(),
+    ),
+    (
+      "network_transmit_errors_total",
+      "Total transmit errors on the network interface.",
+      |data| data.total_errors_on_transmitted(),
+    ),
+  ];
+
+  for (name, help, value) in network_counters {
+    exposition.family(name, "counter", help);
+    for (interface, data) in networks.iter() {
+      exposition.sample(name, &[("interface", interface)], value(data) as f64);
+    }
+  }
+
+  exposition.family("uptime_seconds", "gauge", "System uptime in seconds.");
+  exposition.sample("uptime_seconds", &[], System::uptime() as f64);
+
+  exposition.output
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn exposition_writes_help_and_type_lines() {
+    let mut exposition = Exposition::default();
+    exposition.family(
+      "memory_used_bytes",
+      "gauge",
+      "Used physical memory in bytes.",
+    );
+    exposition.sample("memory_used_bytes", &[], 1024.0);
+    exposition.family("network_received_bytes_total", "counter", "Total bytes.");
+    exposition.sample(
+      "network_received_bytes_total",
+      &[("interface", "eth0"), ("kind", "wired")],
+      12.5,
+    );
+
+    assert_eq!(
+      exposition.output,
+      "# HELP hardware_monitor_memory_used_bytes Used physical memory in bytes.\n\
+       # TYPE hardware_monitor_memory_used_bytes gauge\n\
+       hardware_monitor_memory_used_bytes 1024\n\
+       # HELP hardware_monitor_network_received_bytes_total Total bytes.\n\
+       # TYPE hardware_monitor_network_received_bytes_total counter\n\
+       hardware_monitor_network_received_bytes_total{interface=\"eth0\",kind=\"wired\"} 12.5\n"
+    );
+  }
+
+  #[test]
+  fn exposition_escapes_label_values() {
+    let mut exposition = Exposition::default();
+    exposition.sample(
+      "sensor_temperature_celsius",
+      &[("sensor", "a\\b\"c\nd")],
+      40.0,
+    );
+
+    assert_eq!(
+      exposition.output,
+      "hardware_monitor_sensor_temperature_celsius{sensor=\"a\\\\b\\\"c\\nd\"} 40\n"
+    );
+  }
+
+  #[test]
+  fn exposition_skips_non_finite_values() {
+    let mut exposition = Exposition::default();
+    exposition.sample("gpu_usage_percent", &[], f64::NAN);
+    exposition.sample("gpu_usage_percent", &[], f64::INFINITY);
+    exposition.sample("gpu_usage_percent", &[], f64::NEG_INFINITY);
+
+    assert_eq!(exposition.output, "");
+  }
+
+  #[test]
+  fn unique_labels_numbers_duplicates_in_order() {
+    let labels = unique_labels(
+      ["Composite", "Tctl", "Composite", "Composite"]
+        .iter()
+        .copied(),
+    );
+
+    assert_eq!(
+      labels,
+      ["Composite", "Tctl", "Composite #2", "Composite #3"]
+    );
+  }
+
+  #[test]
+  fn render_metrics_separates_total_cpu_and_labels_gpus_by_name() {
+    let gpu = GpuSnapshot {
+      usage: Some(50.0),
+      temperatures: vec![
+        NameValue {
+          name: "GPU".to_string(),
+          value: 60.0,
+        },
+        NameValue {
+          name: "GPU".to_string(),
+          value: 62.0,
+        },
+      ],
+    };
+
+    let output = render_metrics(
+      &System::new(),
+      &Disks::new(),
+      &Networks::new(),
+      &Components::new(),
+      &gpu,
+    );
+
+    assert!(output.contains("# TYPE hardware_monitor_cpu_usage_total_percent gauge\n"));
+    assert!(!output.contains("cpu=\"total\""));
+    assert!(output.contains("hardware_monitor_gpu_usage_percent 50\n"));
+    assert!(output.contains("hardware_monitor_gpu_temperature_celsius{gpu=\"GPU\"} 60\n"));
+    assert!(
+      output.contains("hardware_monitor_gpu_temperature_celsius{gpu=\"GPU #2\"} 62\n")
+    );
+  }
+}
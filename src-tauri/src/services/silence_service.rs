@@ -0,0 +1,300 @@
+use chrono::{Datelike, Duration, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+///
+/// 一時的に通知を止める時間・毎週のメンテナンス期間の長さの上限（7日間）
+///
+pub const MAX_SILENCE_MINUTES: u32 = 7 * 24 * 60;
+
+///
+/// 同じミリ秒に作成した通知の停止の ID が重複しないよう、ID の末尾に付ける連番
+///
+static SILENCE_SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
+///
+/// メンテナンス期間のスケジュール
+///
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum MaintenanceSchedule {
+  ///
+  /// 1回限りの期間（UNIX エポックからのミリ秒）
+  ///
+  #[serde(rename_all = "camelCase")]
+  OneOff { start: i64, end: i64 },
+  ///
+  /// 毎週の指定した曜日の指定した時刻から（ローカル時刻）
+  ///
+  #[serde(rename_all = "camelCase")]
+  Recurring {
+    days_of_week: Vec<u32>, // 0: 日曜日 〜 6: 土曜日
+    start_minute: u32,      // 0時からの分数（例: 13:30 は 810）
+    duration_minutes: u32,  // 日付をまたいでもよい
+  },
+}
+
+impl MaintenanceSchedule {
+  ///
+  /// ## 指定した時刻が期間内か
+  ///
+  /// - param now: `i64` UNIX エポックからのミリ秒（`OneOff` の判定に使用）
+  /// - param local_now: `NaiveDateTime` ローカル時刻（`Recurring` の判定に使用）
+  ///
+  pub fn contains(&self, now: i64, local_now: NaiveDateTime) -> bool {
+    match self {
+      MaintenanceSchedule::OneOff { start, end } => *start <= now && now < *end,
+      MaintenanceSchedule::Recurring {
+        days_of_week,
+        start_minute,
+        duration_minutes,
+      } => {
+        let duration = Duration::minutes(*duration_minutes as i64);
+        // 前日以前に始まって日付をまたいでいる期間も判定する
+        let max_days_back = (*start_minute as i64 + *duration_minutes as i64) / (24 * 60);
+
+        (0..=max_days_back).any(|days_back| {
+          let date = local_now.date() - Duration::days(days_back);
+          if !days_of_week.contains(&date.weekday().num_days_from_sunday()) {
+            return false;
+          }
+
+          let start =
+            date.and_hms_opt(0, 0, 0).unwrap() + Duration::minutes(*start_minute as i64);
+          start <= local_now && local_now < start + duration
+        })
+      }
+    }
+  }
+
+  fn validate(&self) -> Result<(), String> {
+    match self {
+      MaintenanceSchedule::OneOff { start, end } => {
+        if start >= end {
+          return Err("Maintenance window must end after it starts".to_string());
+        }
+      }
+      MaintenanceSchedule::Recurring {
+        days_of_week,
+        start_minute,
+        duration_minutes,
+      } => {
+        if days_of_week.is_empty() || days_of_week.iter().any(|day| *day > 6) {
+          return Err(
+            "Days of week must be between 0 (Sunday) and 6 (Saturday)".to_string(),
+          );
+        }
+        if *start_minute >= 24 * 60 {
+          return Err("Start time must be before 24:00".to_string());
+        }
+        if *duration_minutes == 0 || *duration_minutes > MAX_SILENCE_MINUTES {
+          return Err(format!(
+            "Duration must be between 1 and {} minutes",
+            MAX_SILENCE_MINUTES
+          ));
+        }
+      }
+    }
+
+    Ok(())
+  }
+}
+
+///
+/// アラートを通知しないメンテナンス期間（ベンチマークの実行中など）
+///
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct MaintenanceWindow {
+  pub id: String,
+  pub name: String,
+  pub schedule: MaintenanceSchedule,
+  #[serde(default)]
+  pub rule_ids: Vec<String>, // 空の場合はすべてのルールが対象
+  #[serde(default = "default_true")]
+  pub enabled: bool,
+}
+
+fn default_true() -> bool {
+  true
+}
+
+impl MaintenanceWindow {
+  ///
+  /// ## メンテナンス期間の内容を検証
+  ///
+  pub fn validate(&self) -> Result<(), String> {
+    if self.name.trim().is_empty() {
+      return Err("Maintenance window requires a name".to_string());
+    }
+    self.schedule.validate()
+  }
+}
+
+///
+/// 一時的にアラートの通知を止める（「N 分間通知しない」）
+///
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Silence {
+  pub id: String,
+  pub rule_id: Option<String>, // `None` の場合はすべてのルールが対象
+  pub created_at: i64,         // UNIX エポックからのミリ秒
+  pub until: i64,
+}
+
+impl Silence {
+  ///
+  /// ## 現在時刻から指定した時間だけ通知しない
+  ///
+  /// - param now: `i64` 現在時刻（UNIX エポックからのミリ秒）
+  /// - param minutes: `u64` 通知しない時間（分、1 〜 `MAX_SILENCE_MINUTES`）
+  ///
+  pub fn new(rule_id: Option<String>, now: i64, minutes: u64) -> Result<Self, String> {
+    if minutes == 0 || minutes > MAX_SILENCE_MINUTES as u64 {
+      return Err(format!(
+        "Silence must last between 1 and {} minutes",
+        MAX_SILENCE_MINUTES
+      ));
+    }
+
+    let until = i64::try_from(minutes)
+      .ok()
+      .and_then(|minutes| minutes.checked_mul(60 * 1000))
+      .and_then(|millis| now.checked_add(millis))
+      .ok_or_else(|| "Silence end time is out of range".to_string())?;
+
+    Ok(Silence {
+      id: format!(
+        "silence-{}-{}",
+        now,
+        SILENCE_SEQUENCE.fetch_add(1, Ordering::Relaxed)
+      ),
+      rule_id,
+      created_at: now,
+      until,
+    })
+  }
+
+  pub fn is_active(&self, now: i64) -> bool {
+    now < self.until
+  }
+}
+
+///
+/// ## ルールのアラートを通知しないか
+///
+/// - param now: `i64` 現在時刻（UNIX エポックからのミリ秒）
+/// - param local_now: `NaiveDateTime` 現在のローカル時刻（毎週のメンテナンス期間の判定に使用）
+///
+pub fn is_silenced(
+  rule_id: &str,
+  now: i64,
+  local_now: NaiveDateTime,
+  windows: &[MaintenanceWindow],
+  silences: &[Silence],
+) -> bool {
+  let in_window = windows.iter().any(|window| {
+    window.enabled
+      && (window.rule_ids.is_empty() || window.rule_ids.iter().any(|id| id == rule_id))
+      && window.schedule.contains(now, local_now)
+  });
+
+  in_window
+    || silences.iter().any(|silence| {
+      silence.is_active(now)
+        && silence
+          .rule_id
+          .as_deref()
+          .map_or(true, |silenced_rule_id| silenced_rule_id == rule_id)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use chrono::NaiveDate;
+
+  fn at(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(year, month, day)
+      .unwrap()
+      .and_hms_opt(hour, minute, 0)
+      .unwrap()
+  }
+
+  fn recurring(
+    days_of_week: Vec<u32>,
+    start_minute: u32,
+    duration_minutes: u32,
+  ) -> MaintenanceSchedule {
+    MaintenanceSchedule::Recurring {
+      days_of_week,
+      start_minute,
+      duration_minutes,
+    }
+  }
+
+  #[test]
+  fn recurring_window_crosses_midnight_into_next_week() {
+    // 2024-06-01 は土曜日
+    let schedule = recurring(vec![6], 23 * 60, 120);
+
+    assert!(schedule.contains(0, at(2024, 6, 1, 23, 30)));
+    assert!(schedule.contains(0, at(2024, 6, 2, 0, 30)));
+    assert!(!schedule.contains(0, at(2024, 6, 2, 1, 30)));
+    // 日曜日に始まる期間ではない
+    assert!(!schedule.contains(0, at(2024, 6, 2, 23, 30)));
+  }
+
+  #[test]
+  fn recurring_window_includes_start_and_excludes_end() {
+    // 2024-06-03 は月曜日
+    let schedule = recurring(vec![1], 9 * 60, 30);
+
+    assert!(!schedule.contains(0, at(2024, 6, 3, 8, 59)));
+    assert!(schedule.contains(0, at(2024, 6, 3, 9, 0)));
+    assert!(schedule.contains(0, at(2024, 6, 3, 9, 29)));
+    assert!(!schedule.contains(0, at(2024, 6, 3, 9, 30)));
+    assert!(!schedule.contains(0, at(2024, 6, 4, 9, 0)));
+  }
+
+  #[test]
+  fn recurring_window_spanning_days_checks_earlier_start_days() {
+    // 金曜日 22:00 から 3日間
+    let schedule = recurring(vec![5], 22 * 60, 3 * 24 * 60);
+
+    assert!(schedule.contains(0, at(2024, 6, 3, 21, 59)));
+    assert!(!schedule.contains(0, at(2024, 6, 3, 22, 0)));
+    assert!(!schedule.contains(0, at(2024, 6, 7, 21, 59)));
+  }
+
+  #[test]
+  fn one_off_window_includes_start_and_excludes_end() {
+    let schedule = MaintenanceSchedule::OneOff {
+      start: 1_000,
+      end: 2_000,
+    };
+    let local_now = at(2024, 6, 3, 0, 0);
+
+    assert!(!schedule.contains(999, local_now));
+    assert!(schedule.contains(1_000, local_now));
+    assert!(!schedule.contains(2_000, local_now));
+  }
+
+  #[test]
+  fn silences_created_in_same_millisecond_have_unique_ids() {
+    let first = Silence::new(None, 1_000, 5).unwrap();
+    let second = Silence::new(None, 1_000, 5).unwrap();
+
+    assert_ne!(first.id, second.id);
+    assert!(first.id.starts_with("silence-1000-"));
+  }
+
+  #[test]
+  fn silence_bounds_duration() {
+    assert!(Silence::new(None, 0, 0).is_err());
+    assert!(Silence::new(None, 0, MAX_SILENCE_MINUTES as u64 + 1).is_err());
+    assert!(Silence::new(None, i64::MAX - 10, 1).is_err());
+    assert_eq!(Silence::new(None, 5, 1).unwrap().until, 60_005);
+  }
+}
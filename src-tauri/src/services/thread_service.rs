@@ -0,0 +1,148 @@
+use crate::services::procfs_service;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+///
+/// 最後に取得されてからスレッドの計測を続ける期間
+///
+const TRACKING_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ThreadInfo {
+  pub tid: u32,
+  pub name: String,
+  pub state: String,
+  pub cpu_usage: Option<f32>, // 直近のサンプリング間隔での使用率（初回は `None`）
+  pub cpu_time_secs: f64,     // 累積の CPU 時間（user + system）
+}
+
+#[derive(Debug)]
+struct TrackedProcess {
+  requested_at: Instant,
+  sampled_at: Option<Instant>,
+  cpu_ticks: HashMap<u32, u64>, // 前回計測時のスレッドごとの累積ティック数
+  cpu_usage: HashMap<u32, f32>,
+}
+
+///
+/// 取得要求のあったプロセスについて、サンプリングごとにスレッドの CPU 使用率を計算する
+///
+/// - 全プロセスのスレッドを毎回読み込むとコストが高いため、対象を限定する
+///
+#[derive(Debug, Default)]
+pub struct ThreadSampler {
+  tracked: HashMap<u32, TrackedProcess>,
+}
+
+impl ThreadSampler {
+  ///
+  /// ## 計測対象のプロセスのスレッドを計測
+  ///
+  /// - 一定期間取得されていないプロセスや終了したプロセスは対象から外す
+  ///
+  pub fn sample(&mut self) {
+    let ticks_per_second = procfs_service::clock_ticks_per_second() as f32;
+
+    self.tracked.retain(|pid, tracked| {
+      if tracked.requested_at.elapsed() > TRACKING_TIMEOUT {
+        return false;
+      }
+
+      let tids = match procfs_service::list_thread_ids(*pid) {
+        Ok(tids) => tids,
+        Err(_) => return false,
+      };
+
+      let now = Instant::now();
+      let elapsed = tracked
+        .sampled_at
+        .map(|sampled_at| now.duration_since(sampled_at).as_secs_f32());
+
+      let mut cpu_ticks = HashMap::with_capacity(tids.len());
+      let mut cpu_usage = HashMap::with_capacity(tids.len());
+
+      for tid in tids {
+        let (_, stat) = match procfs_service::read_thread(*pid, tid) {
+          Some(thread) => thread,
+          None => continue, // 読み込み中に終了したスレッド
+        };
+        let ticks = stat.utime + stat.stime;
+
+        if let (Some(elapsed), Some(last)) = (elapsed, tracked.cpu_ticks.get(&tid)) {
+          if elapsed > 0.0 {
+            let seconds = ticks.saturating_sub(*last) as f32 / ticks_per_second;
+            let usage = seconds / elapsed * 100.0;
+            cpu_usage.insert(tid, (usage * 10.0).round() / 10.0);
+          }
+        }
+
+        cpu_ticks.insert(tid, ticks);
+      }
+
+      tracked.sampled_at = Some(now);
+      tracked.cpu_ticks = cpu_ticks;
+      tracked.cpu_usage = cpu_usage;
+
+      true
+    });
+  }
+
+  ///
+  /// ## プロセスのスレッド一覧を取得
+  ///
+  /// - 初めて取得するプロセスは次のサンプリングから CPU 使用率の計算を開始する
+  ///
+  pub fn threads(&mut self, pid: u32) -> Result<Vec<ThreadInfo>, String> {
+    let tids = procfs_service::list_thread_ids(pid)?;
+    let ticks_per_second = procfs_service::clock_ticks_per_second() as f64;
+
+    let tracked = self.tracked.entry(pid).or_insert_with(|| TrackedProcess {
+      requested_at: Instant::now(),
+      sampled_at: None,
+      cpu_ticks: HashMap::new(),
+      cpu_usage: HashMap::new(),
+    });
+    tracked.requested_at = Instant::now();
+
+    Ok(
+      tids
+        .into_iter()
+        .filter_map(|tid| {
+          let (name, stat) = procfs_service::read_thread(pid, tid)?;
+
+          Some(ThreadInfo {
+            tid,
+            name,
+            state: thread_state_name(stat.state).to_string(),
+            cpu_usage: tracked.cpu_usage.get(&tid).copied(),
+            cpu_time_secs: (stat.utime + stat.stime) as f64 / ticks_per_second,
+          })
+        })
+        .collect(),
+    )
+  }
+}
+
+///
+/// ## `stat` の状態コードを名前に変換
+///
+/// - `ProcessDetails.status` と揃えるため、sysinfo の `ProcessStatus` の表記を使用する
+///
+fn thread_state_name(state: char) -> &'static str {
+  match state {
+    'R' => "Runnable",
+    'S' => "Sleeping",
+    'I' => "Idle",
+    'D' => "UninterruptibleDiskSleep",
+    'Z' => "Zombie",
+    'T' => "Stopped",
+    't' => "Tracing",
+    'X' | 'x' => "Dead",
+    'K' => "Wakekill",
+    'W' => "Waking",
+    'P' => "Parked",
+    _ => "Unknown",
+  }
+}
@@ -0,0 +1,646 @@
+use crate::services::alert_service::{AlertEvent, AlertEventKind};
+use crate::utils::file::get_app_data_dir;
+use crate::{log_debug, log_error, log_info, log_internal, log_warn};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::collections::VecDeque;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+///
+/// 送信結果を記録するファイル名
+///
+const DELIVERY_LOG_FILENAME: &str = "webhook_deliveries.log";
+
+///
+/// メモリ上に保持する送信結果の件数
+///
+const DELIVERY_LOG_CAPACITY: usize = 200;
+
+///
+/// 再送回数の上限
+///
+const MAX_RETRIES: u32 = 10;
+
+///
+/// 再送までの待ち時間の上限
+///
+const MAX_BACKOFF_MILLIS: u64 = 5 * 60 * 1000;
+
+///
+/// 1回の送信のタイムアウトの上限
+///
+const MAX_TIMEOUT_SECS: u64 = 5 * 60;
+
+///
+/// アラートを送信する Webhook
+///
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookTarget {
+  pub id: String,
+  pub name: String,
+  pub url: String,
+  #[serde(default)]
+  pub headers: HashMap<String, String>,
+  #[serde(default)]
+  pub body_template: Option<String>, // `{{ruleName}}` などを置き換える JSON（省略時は `AlertEvent` を送信）
+  #[serde(default = "default_max_retries")]
+  pub max_retries: u32,
+  #[serde(default = "default_initial_backoff_millis")]
+  pub initial_backoff_millis: u64, // 再送のたびに 2倍にする
+  #[serde(default = "default_timeout_secs")]
+  pub timeout_secs: u64,
+  #[serde(default = "default_true")]
+  pub enabled: bool,
+}
+
+fn default_max_retries() -> u32 {
+  3
+}
+
+fn default_initial_backoff_millis() -> u64 {
+  1000
+}
+
+fn default_timeout_secs() -> u64 {
+  10
+}
+
+fn default_true() -> bool {
+  true
+}
+
+impl WebhookTarget {
+  ///
+  /// ## Webhook の設定を検証
+  ///
+  pub fn validate(&self) -> Result<(), String> {
+    let url = reqwest::Url::parse(&self.url)
+      .map_err(|e| format!("Invalid webhook URL: {}", e))?;
+    if !matches!(url.scheme(), "http" | "https") {
+      return Err(format!("Unsupported URL scheme: {}", url.scheme()));
+    }
+
+    for (name, value) in &self.headers {
+      reqwest::header::HeaderName::from_bytes(name.as_bytes())
+        .map_err(|e| format!("Invalid header name {}: {}", name, e))?;
+      reqwest::header::HeaderValue::from_str(value)
+        .map_err(|e| format!("Invalid header value for {}: {}", name, e))?;
+    }
+
+    if self.timeout_secs == 0 || self.timeout_secs > MAX_TIMEOUT_SECS {
+      return Err(format!(
+        "Timeout must be between 1 and {} seconds: {}",
+        MAX_TIMEOUT_SECS, self.timeout_secs
+      ));
+    }
+    if self.max_retries > MAX_RETRIES {
+      return Err(format!(
+        "Max retries must be at most {}: {}",
+        MAX_RETRIES, self.max_retries
+      ));
+    }
+    if self.initial_backoff_millis == 0
+      || self.initial_backoff_millis > MAX_BACKOFF_MILLIS
+    {
+      return Err(format!(
+        "Initial backoff must be between 1 and {} milliseconds: {}",
+        MAX_BACKOFF_MILLIS, self.initial_backoff_millis
+      ));
+    }
+
+    // サンプルの値で展開し、JSON として解釈できるか確認する
+    if let Some(template) = &self.body_template {
+      render_template(template, &template_values_sample())?;
+    }
+
+    Ok(())
+  }
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookDelivery {
+  pub webhook_id: String,
+  pub webhook_name: String,
+  pub rule_id: Option<String>, // テスト送信の場合は `None`
+  pub event_kind: Option<AlertEventKind>,
+  pub attempts: u32,
+  pub status_code: Option<u16>,
+  pub success: bool,
+  pub error: Option<String>,
+  pub delivered_at: i64, // UNIX エポックからのミリ秒
+}
+
+///
+/// 送信結果の記録（直近の結果はメモリに保持し、すべての結果をファイルに追記する）
+///
+#[derive(Debug, Default)]
+pub struct WebhookDeliveryLog {
+  deliveries: VecDeque<WebhookDelivery>,
+}
+
+impl WebhookDeliveryLog {
+  pub fn push(&mut self, delivery: WebhookDelivery) {
+    append_delivery_log(&delivery);
+
+    if self.deliveries.len() >= DELIVERY_LOG_CAPACITY {
+      self.deliveries.pop_front();
+    }
+    self.deliveries.push_back(delivery);
+  }
+
+  ///
+  /// ## 直近の送信結果を取得（新しい順）
+  ///
+  pub fn recent(&self, limit: usize) -> Vec<WebhookDelivery> {
+    self.deliveries.iter().rev().take(limit).cloned().collect()
+  }
+}
+
+fn append_delivery_log(delivery: &WebhookDelivery) {
+  let serialized = match serde_json::to_string(delivery) {
+    Ok(serialized) => serialized,
+    Err(e) => {
+      log_error!(
+        "serialize_failed",
+        "append_delivery_log",
+        Some(e.to_string())
+      );
+      return;
+    }
+  };
+
+  let log_file = get_app_data_dir(DELIVERY_LOG_FILENAME);
+  if let Some(parent) = log_file.parent() {
+    if let Err(e) = std::fs::create_dir_all(parent) {
+      log_error!(
+        "create_dir_failed",
+        "append_delivery_log",
+        Some(e.to_string())
+      );
+      return;
+    }
+  }
+
+  if let Err(e) = OpenOptions::new()
+    .create(true)
+    .append(true)
+    .open(log_file)
+    .and_then(|mut file| writeln!(file, "{}", serialized))
+  {
+    log_warn!("write_failed", "append_delivery_log", Some(e.to_string()));
+  }
+}
+
+///
+/// ## テンプレートに埋め込むアラートの値
+///
+/// - 文字列は JSON の文字列としてエスケープ済み（テンプレート側で `"` で囲む）
+///
+pub fn template_values(event: &AlertEvent) -> HashMap<&'static str, String> {
+  let kind = match event.kind {
+    AlertEventKind::Fired => "fired",
+    AlertEventKind::Resolved => "resolved",
+  };
+  let metric = serde_json::to_value(event.metric)
+    .ok()
+    .and_then(|value| value.as_str().map(str::to_string))
+    .unwrap_or_default();
+
+  HashMap::from([
+    ("kind", kind.to_string()),
+    ("ruleId", escape_json(&event.rule_id)),
+    ("ruleName", escape_json(&event.rule_name)),
+    ("metric", metric),
+    ("unit", escape_json(event.metric.unit())),
+    ("value", event.value.to_string()),
+    ("peakValue", event.peak_value.to_string()),
+    ("threshold", event.threshold.to_string()),
+    ("startedAt", event.started_at.to_string()),
+    ("firedAt", event.fired_at.to_string()),
+    (
+      "resolvedAt",
+      event
+        .resolved_at
+        .map_or("null".to_string(), |value| value.to_string()),
+    ),
+    ("message", escape_json(&event.message())),
+  ])
+}
+
+///
+/// ## テスト送信や検証に使用するサンプルの値
+///
+pub fn template_values_sample() -> HashMap<&'static str, String> {
+  HashMap::from([
+    ("kind", "fired".to_string()),
+    ("ruleId", "sample".to_string()),
+    ("ruleName", "Sample".to_string()),
+    ("metric", "cpuUsage".to_string()),
+    ("unit", "%".to_string()),
+    ("value", "0".to_string()),
+    ("peakValue", "0".to_string()),
+    ("threshold", "0".to_string()),
+    ("startedAt", "0".to_string()),
+    ("firedAt", "0".to_string()),
+    ("resolvedAt", "null".to_string()),
+    ("message", "Sample".to_string()),
+  ])
+}
+
+fn escape_json(value: &str) -> String {
+  let quoted = serde_json::to_string(value).unwrap_or_default();
+  quoted[1..quoted.len() - 1].to_string()
+}
+
+///
+/// ## テンプレートの `{{name}}` を置き換えて JSON として解釈
+///
+/// - 置き換えた値の中の `{{name}}` は展開しない
+/// - 未知の名前はそのまま残す
+///
+pub fn render_template(
+  template: &str,
+  values: &HashMap<&'static str, String>,
+) -> Result<Value, String> {
+  let mut rendered = String::with_capacity(template.len());
+  let mut rest = template;
+
+  while let Some(start) = rest.find("{{") {
+    let end = match rest[start + 2..].find("}}") {
+      Some(end) => end,
+      None => break,
+    };
+    let name = &rest[start + 2..start + 2 + end];
+
+    rendered.push_str(&rest[..start]);
+    match values.get(name) {
+      Some(value) => rendered.push_str(value),
+      None => rendered.push_str(&rest[start..start + end + 4]),
+    }
+    rest = &rest[start + end + 4..];
+  }
+  rendered.push_str(rest);
+
+  serde_json::from_str(&rendered).map_err(|e| format!("Invalid body template: {}", e))
+}
+
+///
+/// ## Webhook へ送信（失敗した場合は待ち時間を倍にしながら再送）
+///
+/// - 接続エラー・タイムアウト・5xx・429 の場合のみ再送する（不正なヘッダーなどでリクエストを組み立てられない場合は再送しない）
+/// - 待ち時間は `MAX_BACKOFF_MILLIS` で頭打ちにする
+///
+pub async fn deliver(
+  client: &reqwest::Client,
+  target: &WebhookTarget,
+  body: &Value,
+) -> (u32, Result<u16, (Option<u16>, String)>) {
+  let mut attempts = 0;
+  let max_backoff = Duration::from_millis(MAX_BACKOFF_MILLIS);
+  let mut backoff = Duration::from_millis(target.initial_backoff_millis).min(max_backoff);
+
+  loop {
+    attempts += 1;
+
+    let mut request = client
+      .post(&target.url)
+      .timeout(Duration::from_secs(target.timeout_secs))
+      .json(body);
+    for (name, value) in &target.headers {
+      request = request.header(name, value);
+    }
+
+    let (retryable, result) = match request.send().await {
+      Ok(response) => {
+        let status = response.status();
+        if status.is_success() {
+          (false, Ok(status.as_u16()))
+        } else {
+          (
+            status.is_server_error() || status.as_u16() == 429,
+            Err((Some(status.as_u16()), format!("HTTP {}", status))),
+          )
+        }
+      }
+      Err(e) => (!e.is_builder(), Err((None, e.to_string()))),
+    };
+
+    if result.is_ok() || !retryable || attempts > target.max_retries.min(MAX_RETRIES) {
+      return (attempts, result);
+    }
+
+    log_debug!(
+      "retry",
+      "webhook_service::deliver",
+      Some(format!("{} in {:?}", target.url, backoff))
+    );
+    tokio::time::sleep(backoff).await;
+    backoff = backoff.saturating_mul(2).min(max_backoff);
+  }
+}
+
+///
+/// ## アラートを Webhook へ送信し、結果を記録
+///
+/// - サンプリングのループを止めないよう非同期に実行する
+///
+pub fn spawn(
+  client: reqwest::Client,
+  target: WebhookTarget,
+  event: &AlertEvent,
+  delivery_log: Arc<Mutex<WebhookDeliveryLog>>,
+) {
+  let body = match &target.body_template {
+    Some(template) => render_template(template, &template_values(event)),
+    None => serde_json::to_value(event).map_err(|e| e.to_string()),
+  };
+  let rule_id = event.rule_id.clone();
+  let event_kind = event.kind;
+
+  tauri::async_runtime::spawn(async move {
+    let (attempts, result) = match body {
+      Ok(body) => deliver(&client, &target, &body).await,
+      Err(e) => (0, Err((None, e))),
+    };
+
+    let delivery =
+      to_delivery(&target, Some(rule_id), Some(event_kind), attempts, result);
+    if delivery.success {
+      log_info!("delivered", "webhook_service::spawn", Some(&target.name));
+    } else {
+      log_warn!(
+        "delivery_failed",
+        "webhook_service::spawn",
+        Some(format!("{}: {:?}", target.name, delivery.error))
+      );
+    }
+
+    delivery_log.lock().unwrap().push(delivery);
+  });
+}
+
+pub fn to_delivery(
+  target: &WebhookTarget,
+  rule_id: Option<String>,
+  event_kind: Option<AlertEventKind>,
+  attempts: u32,
+  result: Result<u16, (Option<u16>, String)>,
+) -> WebhookDelivery {
+  let (status_code, error) = match result {
+    Ok(status_code) => (Some(status_code), None),
+    Err((status_code, error)) => (status_code, Some(error)),
+  };
+
+  WebhookDelivery {
+    webhook_id: target.id.clone(),
+    webhook_name: target.name.clone(),
+    rule_id,
+    event_kind,
+    attempts,
+    success: error.is_none(),
+    status_code,
+    error,
+    delivered_at: chrono::Utc::now().timestamp_millis(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::enums::metric::MetricKind;
+  use crate::services::alert_service::AlertComparison;
+  use tokio::io::{AsyncReadExt, AsyncWriteExt};
+  use tokio::net::TcpListener;
+
+  ///
+  /// 指定したステータスコードを順に返すローカルの HTTP サーバー
+  ///
+  /// - 受信したリクエストのボディを記録する
+  /// - ステータスコードを使い切った後は 200 を返す
+  ///
+  async fn mock_server(statuses: Vec<u16>) -> (String, Arc<Mutex<Vec<String>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/hook", listener.local_addr().unwrap());
+    let bodies = Arc::new(Mutex::new(Vec::new()));
+    let received = bodies.clone();
+
+    tokio::spawn(async move {
+      let mut statuses = statuses.into_iter();
+      loop {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let body = read_request_body(&mut stream).await;
+        received.lock().unwrap().push(body);
+
+        let status = statuses.next().unwrap_or(200);
+        let response = format!(
+          "HTTP/1.1 {} Mock\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+          status
+        );
+        stream.write_all(response.as_bytes()).await.unwrap();
+        stream.shutdown().await.ok();
+      }
+    });
+
+    (url, bodies)
+  }
+
+  async fn read_request_body(stream: &mut tokio::net::TcpStream) -> String {
+    let mut request = Vec::new();
+    let mut buffer = [0u8; 1024];
+
+    loop {
+      let read = stream.read(&mut buffer).await.unwrap();
+      if read == 0 {
+        break;
+      }
+      request.extend_from_slice(&buffer[..read]);
+
+      let text = String::from_utf8_lossy(&request);
+      if let Some(header_end) = text.find("\r\n\r\n") {
+        let content_length = text[..header_end]
+          .lines()
+          .find_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            name
+              .eq_ignore_ascii_case("content-length")
+              .then(|| value.trim().parse::<usize>().ok())?
+          })
+          .unwrap_or(0);
+        if request.len() >= header_end + 4 + content_length {
+          return text[header_end + 4..header_end + 4 + content_length].to_string();
+        }
+      }
+    }
+
+    String::new()
+  }
+
+  fn target(url: &str, max_retries: u32) -> WebhookTarget {
+    WebhookTarget {
+      id: "webhook-1".to_string(),
+      name: "Test".to_string(),
+      url: url.to_string(),
+      headers: HashMap::new(),
+      body_template: None,
+      max_retries,
+      initial_backoff_millis: 1,
+      timeout_secs: 5,
+      enabled: true,
+    }
+  }
+
+  fn event(rule_name: &str) -> AlertEvent {
+    AlertEvent {
+      kind: AlertEventKind::Fired,
+      rule_id: "alert-1".to_string(),
+      rule_name: rule_name.to_string(),
+      metric: MetricKind::CpuUsage,
+      comparison: AlertComparison::Above,
+      threshold: 90.0,
+      value: 95.0,
+      peak_value: 95.0,
+      started_at: 1000,
+      fired_at: 2000,
+      resolved_at: None,
+      duration_millis: None,
+      silenced: false,
+    }
+  }
+
+  #[tokio::test]
+  async fn retries_on_server_error_and_too_many_requests() {
+    let (url, bodies) = mock_server(vec![500, 503, 429]).await;
+    let body = serde_json::json!({ "text": "hello" });
+
+    let (attempts, result) =
+      deliver(&reqwest::Client::new(), &target(&url, 3), &body).await;
+
+    assert_eq!(attempts, 4);
+    assert_eq!(result, Ok(200));
+    let bodies = bodies.lock().unwrap();
+    assert_eq!(bodies.len(), 4);
+    assert!(bodies
+      .iter()
+      .all(|received| received == r#"{"text":"hello"}"#));
+  }
+
+  #[tokio::test]
+  async fn gives_up_after_max_retries() {
+    let (url, bodies) = mock_server(vec![500, 500, 500]).await;
+
+    let (attempts, result) =
+      deliver(&reqwest::Client::new(), &target(&url, 2), &Value::Null).await;
+
+    assert_eq!(attempts, 3);
+    assert_eq!(result.unwrap_err().0, Some(500));
+    assert_eq!(bodies.lock().unwrap().len(), 3);
+  }
+
+  #[tokio::test]
+  async fn does_not_retry_on_client_error() {
+    for status in [400, 401, 404] {
+      let (url, bodies) = mock_server(vec![status]).await;
+
+      let (attempts, result) =
+        deliver(&reqwest::Client::new(), &target(&url, 3), &Value::Null).await;
+
+      assert_eq!(attempts, 1);
+      assert_eq!(result.unwrap_err().0, Some(status));
+      assert_eq!(bodies.lock().unwrap().len(), 1);
+    }
+  }
+
+  #[tokio::test]
+  async fn retries_on_connection_error() {
+    // 割り当てられたポートを解放し、接続できない URL にする
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/hook", listener.local_addr().unwrap());
+    drop(listener);
+
+    let (attempts, result) =
+      deliver(&reqwest::Client::new(), &target(&url, 1), &Value::Null).await;
+
+    assert_eq!(attempts, 2);
+    assert_eq!(result.unwrap_err().0, None);
+  }
+
+  #[tokio::test]
+  async fn sends_rendered_template_with_escaped_values() {
+    let (url, bodies) = mock_server(vec![]).await;
+    let rule_name = "CPU \"high\"\nline\\{{message}}";
+    let body = render_template(
+      r#"{"text": "{{ruleName}}", "value": {{value}}, "resolvedAt": {{resolvedAt}}}"#,
+      &template_values(&event(rule_name)),
+    )
+    .unwrap();
+
+    let (_, result) = deliver(&reqwest::Client::new(), &target(&url, 0), &body).await;
+
+    assert_eq!(result, Ok(200));
+    let received: Value = serde_json::from_str(&bodies.lock().unwrap()[0]).unwrap();
+    assert_eq!(received["text"], rule_name);
+    assert_eq!(received["value"], 95.0);
+    assert_eq!(received["resolvedAt"], Value::Null);
+  }
+
+  #[tokio::test]
+  async fn does_not_retry_when_request_cannot_be_built() {
+    let (url, bodies) = mock_server(vec![]).await;
+    let mut webhook = target(&url, 3);
+    webhook
+      .headers
+      .insert("X-Token".to_string(), "line\nbreak".to_string());
+
+    let (attempts, result) =
+      deliver(&reqwest::Client::new(), &webhook, &Value::Null).await;
+
+    assert_eq!(attempts, 1);
+    assert_eq!(result.unwrap_err().0, None);
+    assert!(bodies.lock().unwrap().is_empty());
+  }
+
+  #[test]
+  fn validate_rejects_invalid_headers_and_timeouts() {
+    let url = "https://example.com/hook";
+
+    let mut webhook = target(url, 3);
+    webhook
+      .headers
+      .insert("Authorization".to_string(), "Bearer token".to_string());
+    assert!(webhook.validate().is_ok());
+    webhook
+      .headers
+      .insert("X-Token".to_string(), "line\nbreak".to_string());
+    assert!(webhook.validate().is_err());
+
+    let mut webhook = target(url, 3);
+    webhook.timeout_secs = 0;
+    assert!(webhook.validate().is_err());
+    webhook.timeout_secs = MAX_TIMEOUT_SECS;
+    assert!(webhook.validate().is_ok());
+    webhook.timeout_secs = MAX_TIMEOUT_SECS + 1;
+    assert!(webhook.validate().is_err());
+  }
+
+  #[test]
+  fn validate_rejects_out_of_range_retry_settings() {
+    let url = "https://example.com/hook";
+    assert!(target(url, MAX_RETRIES).validate().is_ok());
+    assert!(target(url, MAX_RETRIES + 1).validate().is_err());
+    assert!(target(url, u32::MAX).validate().is_err());
+
+    let mut webhook = target(url, 3);
+    webhook.initial_backoff_millis = 0;
+    assert!(webhook.validate().is_err());
+    webhook.initial_backoff_millis = MAX_BACKOFF_MILLIS + 1;
+    assert!(webhook.validate().is_err());
+    webhook.initial_backoff_millis = u64::MAX;
+    assert!(webhook.validate().is_err());
+  }
+}
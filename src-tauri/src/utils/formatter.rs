@@ -69,6 +69,15 @@ pub fn format_size(bytes: u64, precision: usize) -> String {
   }
 }
 
+///
+/// ## バイト数を MB に変換
+///
+/// - sysinfo 0.32 の `Process::memory()` は bytes 単位
+///
+pub fn bytes_to_mb(bytes: u64) -> f32 {
+  (bytes as f64 / 1024.0 / 1024.0) as f32
+}
+
 ///
 /// ## ベンダー名をフォーマット
 ///
@@ -2,11 +2,22 @@ import type {
   HardwareInfo,
   NameValues,
   ProcessInfo,
+  ProcessListResponse,
+  ProcessQuery,
 } from "@/types/hardwareDataType";
 import { invoke } from "@tauri-apps/api/core";
 
-export const getProcesses = async (): Promise<ProcessInfo[]> => {
-  return await invoke("get_process_list");
+export const getProcesses = async (
+  query?: ProcessQuery,
+): Promise<ProcessInfo[]> => {
+  const result = await getProcessList(query);
+  return result.processes;
+};
+
+export const getProcessList = async (
+  query?: ProcessQuery,
+): Promise<ProcessListResponse> => {
+  return await invoke("get_process_list", { query: query ?? null });
 };
 
 export const getCpuUsage = async (): Promise<number> => {
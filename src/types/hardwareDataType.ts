@@ -1,12 +1,84 @@
+import type { AnomalyMarker } from "./settingsType";
+
 export type ChartDataType = "cpu" | "memory" | "gpu";
 
 export type HardwareDataType = "temp" | "usage" | "clock";
 
 export type ProcessInfo = {
   pid: number;
+  parentPid: number | null;
   name: string;
+  user: string | null;
+  startTime: number;
   cpuUsage: number;
   memoryUsage: number;
+  memory: ProcessMemoryBreakdown;
+  diskReadBytesPerSec: number;
+  diskWriteBytesPerSec: number;
+  priority: ProcessPriority | null;
+  cpuAffinity: number[] | null;
+};
+
+export type ProcessMemoryBreakdown = {
+  rss: number | null;
+  virtualSize: number | null;
+  shared: number | null;
+  private: number | null;
+  swap: number | null;
+  pss: number | null;
+};
+
+export type ProcessSortKey =
+  | "pid"
+  | "name"
+  | "user"
+  | "startTime"
+  | "cpuUsage"
+  | "memoryUsage"
+  | "rss"
+  | "virtualMemory"
+  | "sharedMemory"
+  | "privateMemory"
+  | "swap"
+  | "pss"
+  | "diskRead"
+  | "diskWrite";
+
+export type ProcessQuery = {
+  sortKey?: ProcessSortKey;
+  sortDirection?: "ascending" | "descending";
+  name?: string;
+  nameRegex?: string;
+  user?: string;
+  minCpuUsage?: number;
+  minMemoryUsage?: number;
+  top?: number;
+  offset?: number;
+  limit?: number;
+};
+
+export type ProcessListResponse = {
+  total: number;
+  processes: ProcessInfo[];
+};
+
+export type ProcessTreeNode = ProcessInfo & {
+  totalCpuUsage: number;
+  totalMemoryUsage: number;
+  children: ProcessTreeNode[];
+};
+
+export type PriorityClass =
+  | "idle"
+  | "belowNormal"
+  | "normal"
+  | "aboveNormal"
+  | "high"
+  | "realtime";
+
+export type ProcessPriority = {
+  class: PriorityClass;
+  nice: number | null;
 };
 
 export type CpuInfo = {
@@ -46,3 +118,79 @@ export type NameValues = Array<{
   name: string;
   value: number;
 }>;
+
+export type ProcessDetails = {
+  pid: number;
+  parentPid: number | null;
+  name: string;
+  cmd: string[];
+  exe: string | null;
+  cwd: string | null;
+  userId: string | null;
+  userName: string | null;
+  groupId: string | null;
+  startTime: number;
+  runTime: number;
+  status: string;
+  threadCount: number | null;
+  cpuTimeSecs: number | null;
+  openFdCount: number | null;
+  environmentCount: number;
+  environmentBytes: number;
+  memory: ProcessMemoryBreakdown;
+  priority: ProcessPriority | null;
+  cpuAffinity: number[] | null;
+};
+
+export type ProcessGroupBy = "executablePath" | "name" | "treeRoot";
+
+export type ProcessGroup = {
+  key: string;
+  name: string;
+  groupBy: ProcessGroupBy;
+  instanceCount: number;
+  pids: number[];
+  cpuUsage: number;
+  memoryUsage: number;
+  diskReadBytesPerSec: number;
+  diskWriteBytesPerSec: number;
+};
+
+export type ProcessHistoryMetric =
+  | "cpuUsage"
+  | "memoryUsage"
+  | "diskRead"
+  | "diskWrite";
+
+export type ProcessHistoryPoint = {
+  timestamp: number;
+  value: number;
+};
+
+export type MemoryLeakSuspect = {
+  pid: number;
+  name: string;
+  startTime: number;
+  growthMbPerHour: number;
+  rSquared: number;
+  currentMemoryMb: number;
+  observedSecs: number;
+  sampleCount: number;
+  detectedAt: number;
+};
+
+export type ThreadInfo = {
+  tid: number;
+  name: string;
+  state: string;
+  cpuUsage: number | null;
+  cpuTimeSecs: number;
+};
+
+export type MetricHistoryPoint = {
+  timestamp: number;
+  value: number;
+  anomaly: AnomalyMarker | null;
+};
+
+export type ExportFormat = "csv" | "ndjson";
@@ -11,3 +11,194 @@ export type Settings = {
     display: SelectedDisplayType;
   };
 };
+
+export type ProcessWatchRule = {
+  id: string;
+  name: string | null;
+  pathPattern: string | null;
+  pid: number | null;
+  expectRunning: boolean;
+};
+
+export type MemoryLeakSettings = {
+  enabled: boolean;
+  windowMinutes: number;
+  minGrowthMbPerHour: number;
+  minRSquared: number;
+};
+
+export type ProcessCpuMode = "irix" | "solaris";
+
+export type MetricKind =
+  | "cpuUsage"
+  | "memoryUsage"
+  | "gpuUsage"
+  | "gpuTemperature";
+
+export type AlertRule = {
+  id: string;
+  name: string;
+  metric: MetricKind;
+  comparison: "above" | "below";
+  threshold: number;
+  durationSecs: number;
+  hysteresis: number;
+  cooldownSecs: number;
+  enabled: boolean;
+  notify: boolean;
+  command: AlertCommand | null;
+  webhookIds: Array<string>;
+};
+
+export type AlertCommand = {
+  program: string;
+  args: Array<string>;
+  env: Record<string, string>;
+  timeoutSecs: number;
+  runOnResolve: boolean;
+};
+
+export type AlertEvent = {
+  kind: "fired" | "resolved";
+  ruleId: string;
+  ruleName: string;
+  metric: MetricKind;
+  comparison: AlertRule["comparison"];
+  threshold: number;
+  value: number;
+  peakValue: number;
+  startedAt: number;
+  firedAt: number;
+  resolvedAt: number | null;
+  durationMillis: number | null;
+  silenced: boolean;
+};
+
+export type AlertRecord = {
+  id: string;
+  ruleId: string;
+  ruleName: string;
+  metric: MetricKind;
+  comparison: AlertRule["comparison"];
+  threshold: number;
+  peakValue: number;
+  startedAt: number;
+  firedAt: number;
+  resolvedAt: number | null;
+  durationMillis: number | null;
+  acknowledged: boolean;
+  acknowledgedAt: number | null;
+  interrupted: boolean;
+  silenced: boolean;
+};
+
+export type AlertHistoryFilter = {
+  ruleId?: string;
+  metric?: MetricKind;
+  from?: number;
+  to?: number;
+  activeOnly?: boolean;
+  unacknowledgedOnly?: boolean;
+  silenced?: boolean;
+  limit?: number;
+};
+
+export type WebhookTarget = {
+  id: string;
+  name: string;
+  url: string;
+  headers: Record<string, string>;
+  bodyTemplate: string | null;
+  maxRetries: number;
+  initialBackoffMillis: number;
+  timeoutSecs: number;
+  enabled: boolean;
+};
+
+export type WebhookDelivery = {
+  webhookId: string;
+  webhookName: string;
+  ruleId: string | null;
+  eventKind: AlertEvent["kind"] | null;
+  attempts: number;
+  statusCode: number | null;
+  success: boolean;
+  error: string | null;
+  deliveredAt: number;
+};
+
+export type AnomalyMethod = "ewma" | "zScore";
+
+export type AnomalyDetectorSettings = {
+  metric: MetricKind;
+  enabled: boolean;
+  method: AnomalyMethod;
+  ewmaAlpha: number;
+  windowSize: number;
+  thresholdSigma: number;
+  minDeviation: number;
+  warmupSamples: number;
+};
+
+export type AnomalyMarker = {
+  metric: MetricKind;
+  method: AnomalyMethod;
+  timestamp: number;
+  value: number;
+  baseline: number;
+  stdDev: number;
+  zScore: number;
+};
+
+export type MaintenanceSchedule =
+  | { type: "oneOff"; start: number; end: number }
+  | {
+      type: "recurring";
+      daysOfWeek: Array<number>;
+      startMinute: number;
+      durationMinutes: number;
+    };
+
+export type MaintenanceWindow = {
+  id: string;
+  name: string;
+  schedule: MaintenanceSchedule;
+  ruleIds: Array<string>;
+  enabled: boolean;
+};
+
+export type Silence = {
+  id: string;
+  ruleId: string | null;
+  createdAt: number;
+  until: number;
+};
+
+export type PrometheusSettings = {
+  enabled: boolean;
+  bindAddress: string;
+  port: number;
+};
+
+export type InfluxSettings = {
+  enabled: boolean;
+  transport: "http" | "udp";
+  url: string;
+  token: string | null;
+  udpAddress: string;
+  measurement: string;
+  tags: Record<string, string>;
+  batchSize: number;
+  flushIntervalSecs: number;
+  timeoutSecs: number;
+  maxBufferedLines: number;
+};
+
+export type OtlpSettings = {
+  enabled: boolean;
+  endpoint: string;
+  headers: Record<string, string>;
+  exportIntervalSecs: number;
+  timeoutSecs: number;
+  resourceAttributes: Record<string, string>;
+};